@@ -0,0 +1,122 @@
+#ifndef POLYBIUS_H
+#define POLYBIUS_H
+
+/* This file is generated by cbindgen from polybius-ffi/src/lib.rs, do not edit it by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The number type of a birth year
+#define POLYBIUS_NUMBER_TYPE_BIRTH_YEAR 0
+
+// The number type of a birth month
+#define POLYBIUS_NUMBER_TYPE_BIRTH_MONTH 1
+
+// The number type of a birth day
+#define POLYBIUS_NUMBER_TYPE_BIRTH_DAY 2
+
+// The number type of the current year
+#define POLYBIUS_NUMBER_TYPE_CURRENT_YEAR 3
+
+// The number type of any other relevant number
+#define POLYBIUS_NUMBER_TYPE_RELEVANT_NUMBER 4
+
+// The result of every fallible function
+typedef enum PolybiusStatus {
+  POLYBIUS_STATUS_OK = 0,
+  // A required pointer was null
+  POLYBIUS_STATUS_NULL_POINTER = 1,
+  // A string was not valid UTF-8
+  POLYBIUS_STATUS_INVALID_UTF8 = 2,
+  // An argument was out of range, like an unknown number type
+  POLYBIUS_STATUS_INVALID_ARGUMENT = 3,
+  // The buffer can't hold the result, the required size was written
+  POLYBIUS_STATUS_BUFFER_TOO_SMALL = 4,
+  // The library failed unexpectedly
+  POLYBIUS_STATUS_INTERNAL = 5,
+} PolybiusStatus;
+
+// An opaque handle to a generated password, wiped from memory when freed
+typedef struct PolybiusPassword PolybiusPassword;
+
+// An opaque handle to the data the passwords are generated from
+typedef struct PolybiusPasswordData PolybiusPasswordData;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates an empty password data. Free it with `polybius_password_data_free`.
+struct PolybiusPasswordData *polybius_password_data_new(void);
+
+// Frees a password data created by `polybius_password_data_new`. Null is ignored.
+//
+// # Safety
+// `data` must be null or a pointer returned by `polybius_password_data_new` that was not freed yet.
+void polybius_password_data_free(struct PolybiusPasswordData *data);
+
+// Adds a number to the numbers poll. `num_type` is one of the `POLYBIUS_NUMBER_TYPE_*` constants.
+//
+// # Safety
+// `data` must be null or a live pointer returned by `polybius_password_data_new`.
+enum PolybiusStatus polybius_password_data_add_number(struct PolybiusPasswordData *data,
+                                                      uint16_t value,
+                                                      uint32_t num_type);
+
+// Adds a text to the text poll. The text is copied.
+//
+// # Safety
+// `data` must be null or a live pointer returned by `polybius_password_data_new`, and `text` must be null or a
+// NUL-terminated string.
+enum PolybiusStatus polybius_password_data_add_text(struct PolybiusPasswordData *data,
+                                                    const char *text);
+
+// Generates a password of `length` bits into `*password`. Free it with `polybius_password_free`.
+//
+// Returns `POLYBIUS_STATUS_INVALID_ARGUMENT` when the settings can't generate a password from the data, like a zero
+// `length`, no texts or no numbers.
+//
+// The password is kept by the handle, so its size can be asked with `polybius_password_len` before copying it out
+// with `polybius_password_copy`.
+//
+// # Safety
+// `data` must be null or a live pointer returned by `polybius_password_data_new` and `password` must be null or
+// point to a writable `PolybiusPassword *`.
+enum PolybiusStatus polybius_generate_password(const struct PolybiusPasswordData *data,
+                                               size_t length,
+                                               bool symbols,
+                                               struct PolybiusPassword **password);
+
+// The size of the buffer `polybius_password_copy` needs, NUL included. Null gives 0.
+//
+// # Safety
+// `password` must be null or a live pointer returned by `polybius_generate_password`.
+size_t polybius_password_len(const struct PolybiusPassword *password);
+
+// Copies the password into `buffer`, as a NUL-terminated UTF-8 string.
+//
+// `required`, when not null, receives the size the buffer needs, NUL included. When the buffer is too small nothing
+// is written to it and `POLYBIUS_STATUS_BUFFER_TOO_SMALL` is returned.
+//
+// # Safety
+// `password` must be null or a live pointer returned by `polybius_generate_password`, `buffer` must be null or point
+// to at least `buffer_size` writable bytes and `required` must be null or point to a writable `size_t`.
+enum PolybiusStatus polybius_password_copy(const struct PolybiusPassword *password,
+                                           char *buffer,
+                                           size_t buffer_size,
+                                           size_t *required);
+
+// Frees a password returned by `polybius_generate_password`. Null is ignored.
+//
+// # Safety
+// `password` must be null or a pointer returned by `polybius_generate_password` that was not freed yet.
+void polybius_password_free(struct PolybiusPassword *password);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* POLYBIUS_H */
@@ -0,0 +1,345 @@
+//! A C ABI for `polybius-lib`
+//!
+//! The header is generated with cbindgen in `include/polybius.h`. Every pointer handed out by this library must be
+//! given back to the matching free function, and every function may be given null pointers.
+
+use std::{
+    ffi::{c_char, CStr},
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr,
+};
+
+use polybius_lib::{
+    password::Password,
+    password_data::{Number, NumberType, PasswordData},
+    password_generation::{GenerationSettings, PasswordGeneration},
+};
+
+/// An opaque handle to the data the passwords are generated from
+pub struct PolybiusPasswordData {
+    inner: PasswordData,
+}
+
+/// An opaque handle to a generated password, wiped from memory when freed
+pub struct PolybiusPassword {
+    inner: Password,
+}
+
+/// The result of every fallible function
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PolybiusStatus {
+    Ok = 0,
+    /// A required pointer was null
+    NullPointer = 1,
+    /// A string was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// An argument was out of range, like an unknown number type
+    InvalidArgument = 3,
+    /// The buffer can't hold the result, the required size was written
+    BufferTooSmall = 4,
+    /// The library failed unexpectedly
+    Internal = 5,
+}
+
+/// The number type of a birth year
+pub const POLYBIUS_NUMBER_TYPE_BIRTH_YEAR: u32 = 0;
+/// The number type of a birth month
+pub const POLYBIUS_NUMBER_TYPE_BIRTH_MONTH: u32 = 1;
+/// The number type of a birth day
+pub const POLYBIUS_NUMBER_TYPE_BIRTH_DAY: u32 = 2;
+/// The number type of the current year
+pub const POLYBIUS_NUMBER_TYPE_CURRENT_YEAR: u32 = 3;
+/// The number type of any other relevant number
+pub const POLYBIUS_NUMBER_TYPE_RELEVANT_NUMBER: u32 = 4;
+
+fn number_type(num_type: u32) -> Option<NumberType> {
+    match num_type {
+        POLYBIUS_NUMBER_TYPE_BIRTH_YEAR => Some(NumberType::BirthYear),
+        POLYBIUS_NUMBER_TYPE_BIRTH_MONTH => Some(NumberType::BirthMonth),
+        POLYBIUS_NUMBER_TYPE_BIRTH_DAY => Some(NumberType::BirthDay),
+        POLYBIUS_NUMBER_TYPE_CURRENT_YEAR => Some(NumberType::CurrentYear),
+        POLYBIUS_NUMBER_TYPE_RELEVANT_NUMBER => Some(NumberType::RelevantNumber),
+        _ => None,
+    }
+}
+
+/// Runs `f`, turning a panic into `PolybiusStatus::Internal` so it never unwinds into C
+fn guard(f: impl FnOnce() -> PolybiusStatus) -> PolybiusStatus {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(PolybiusStatus::Internal)
+}
+
+/// Creates an empty password data. Free it with `polybius_password_data_free`.
+#[no_mangle]
+pub extern "C" fn polybius_password_data_new() -> *mut PolybiusPasswordData {
+    Box::into_raw(Box::new(PolybiusPasswordData {
+        inner: PasswordData::default(),
+    }))
+}
+
+/// Frees a password data created by `polybius_password_data_new`. Null is ignored.
+///
+/// # Safety
+/// `data` must be null or a pointer returned by `polybius_password_data_new` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn polybius_password_data_free(data: *mut PolybiusPasswordData) {
+    if !data.is_null() {
+        drop(Box::from_raw(data));
+    }
+}
+
+/// Adds a number to the numbers poll. `num_type` is one of the `POLYBIUS_NUMBER_TYPE_*` constants.
+///
+/// # Safety
+/// `data` must be null or a live pointer returned by `polybius_password_data_new`.
+#[no_mangle]
+pub unsafe extern "C" fn polybius_password_data_add_number(
+    data: *mut PolybiusPasswordData,
+    value: u16,
+    num_type: u32,
+) -> PolybiusStatus {
+    let Some(data) = data.as_mut() else {
+        return PolybiusStatus::NullPointer;
+    };
+    let Some(num_type) = number_type(num_type) else {
+        return PolybiusStatus::InvalidArgument;
+    };
+    guard(|| {
+        data.inner.numbers_poll.push(Number::new(value, num_type));
+        PolybiusStatus::Ok
+    })
+}
+
+/// Adds a text to the text poll. The text is copied.
+///
+/// # Safety
+/// `data` must be null or a live pointer returned by `polybius_password_data_new`, and `text` must be null or a
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn polybius_password_data_add_text(
+    data: *mut PolybiusPasswordData,
+    text: *const c_char,
+) -> PolybiusStatus {
+    let Some(data) = data.as_mut() else {
+        return PolybiusStatus::NullPointer;
+    };
+    if text.is_null() {
+        return PolybiusStatus::NullPointer;
+    }
+    let Ok(text) = CStr::from_ptr(text).to_str() else {
+        return PolybiusStatus::InvalidUtf8;
+    };
+    guard(|| {
+        data.inner.text_poll.push(text.to_string());
+        PolybiusStatus::Ok
+    })
+}
+
+/// Generates a password of `length` bits into `*password`. Free it with `polybius_password_free`.
+///
+/// Returns `POLYBIUS_STATUS_INVALID_ARGUMENT` when the settings can't generate a password from the data, like a zero
+/// `length`, no texts or no numbers.
+///
+/// The password is kept by the handle, so its size can be asked with `polybius_password_len` before copying it out
+/// with `polybius_password_copy`.
+///
+/// # Safety
+/// `data` must be null or a live pointer returned by `polybius_password_data_new` and `password` must be null or
+/// point to a writable `PolybiusPassword *`.
+#[no_mangle]
+pub unsafe extern "C" fn polybius_generate_password(
+    data: *const PolybiusPasswordData,
+    length: usize,
+    symbols: bool,
+    password: *mut *mut PolybiusPassword,
+) -> PolybiusStatus {
+    let Some(data) = data.as_ref() else {
+        return PolybiusStatus::NullPointer;
+    };
+    let Some(password) = password.as_mut() else {
+        return PolybiusStatus::NullPointer;
+    };
+    let settings = GenerationSettings::builder()
+        .length(length)
+        .symbols(symbols)
+        .build();
+    if settings.validate(&data.inner).is_err() {
+        return PolybiusStatus::InvalidArgument;
+    }
+    guard(|| {
+        let inner = data.inner.generate_password(&settings);
+        *password = Box::into_raw(Box::new(PolybiusPassword { inner }));
+        PolybiusStatus::Ok
+    })
+}
+
+/// The size of the buffer `polybius_password_copy` needs, NUL included. Null gives 0.
+///
+/// # Safety
+/// `password` must be null or a live pointer returned by `polybius_generate_password`.
+#[no_mangle]
+pub unsafe extern "C" fn polybius_password_len(password: *const PolybiusPassword) -> usize {
+    password
+        .as_ref()
+        .map_or(0, |password| password.inner.expose_secret().len() + 1)
+}
+
+/// Copies the password into `buffer`, as a NUL-terminated UTF-8 string.
+///
+/// `required`, when not null, receives the size the buffer needs, NUL included. When the buffer is too small nothing
+/// is written to it and `POLYBIUS_STATUS_BUFFER_TOO_SMALL` is returned.
+///
+/// # Safety
+/// `password` must be null or a live pointer returned by `polybius_generate_password`, `buffer` must be null or point
+/// to at least `buffer_size` writable bytes and `required` must be null or point to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn polybius_password_copy(
+    password: *const PolybiusPassword,
+    buffer: *mut c_char,
+    buffer_size: usize,
+    required: *mut usize,
+) -> PolybiusStatus {
+    let Some(password) = password.as_ref() else {
+        return PolybiusStatus::NullPointer;
+    };
+    guard(|| {
+        write_string(
+            &password.inner.expose_secret(),
+            buffer,
+            buffer_size,
+            required,
+        )
+    })
+}
+
+/// Frees a password returned by `polybius_generate_password`. Null is ignored.
+///
+/// # Safety
+/// `password` must be null or a pointer returned by `polybius_generate_password` that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn polybius_password_free(password: *mut PolybiusPassword) {
+    if !password.is_null() {
+        drop(Box::from_raw(password));
+    }
+}
+
+/// Copies a string with its NUL terminator into a caller buffer
+unsafe fn write_string(
+    string: &str,
+    buffer: *mut c_char,
+    buffer_size: usize,
+    required: *mut usize,
+) -> PolybiusStatus {
+    let size = string.len() + 1;
+    if let Some(required) = required.as_mut() {
+        *required = size;
+    }
+    if buffer.is_null() {
+        return PolybiusStatus::NullPointer;
+    }
+    if buffer_size < size {
+        return PolybiusStatus::BufferTooSmall;
+    }
+    ptr::copy_nonoverlapping(string.as_ptr(), buffer.cast::<u8>(), string.len());
+    *buffer.add(string.len()) = 0;
+    PolybiusStatus::Ok
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    #[test]
+    fn test_generate_password() {
+        unsafe {
+            let data = polybius_password_data_new();
+            let text = CString::new("Cats").unwrap();
+            assert_eq!(
+                polybius_password_data_add_text(data, text.as_ptr()),
+                PolybiusStatus::Ok
+            );
+            assert_eq!(
+                polybius_password_data_add_number(data, 12, POLYBIUS_NUMBER_TYPE_BIRTH_MONTH),
+                PolybiusStatus::Ok
+            );
+
+            let mut password = ptr::null_mut();
+            let status = polybius_generate_password(data, 4, false, &mut password);
+            assert_eq!(status, PolybiusStatus::Ok);
+
+            // The size doesn't change between asking for it and copying
+            let size = polybius_password_len(password);
+            let mut buffer = vec![0 as c_char; size];
+            let mut required = 0;
+            let status = polybius_password_copy(password, buffer.as_mut_ptr(), size, &mut required);
+            assert_eq!(status, PolybiusStatus::Ok);
+            assert_eq!(required, size);
+            let copied = CStr::from_ptr(buffer.as_ptr()).to_str().unwrap();
+            assert_eq!(copied.len() + 1, size);
+            assert!(copied.len() >= 4);
+
+            polybius_password_free(password);
+            polybius_password_data_free(data);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        unsafe {
+            let data = polybius_password_data_new();
+            assert_eq!(
+                polybius_password_data_add_number(data, 1, 42),
+                PolybiusStatus::InvalidArgument
+            );
+            assert_eq!(
+                polybius_password_data_add_text(data, ptr::null()),
+                PolybiusStatus::NullPointer
+            );
+            let invalid = [0xffu8 as c_char, 0];
+            assert_eq!(
+                polybius_password_data_add_text(data, invalid.as_ptr()),
+                PolybiusStatus::InvalidUtf8
+            );
+
+            let text = CString::new("Lemons").unwrap();
+            polybius_password_data_add_text(data, text.as_ptr());
+            let mut password = ptr::null_mut();
+            // Without numbers, the positions drawn as numbers would be skipped
+            assert_eq!(
+                polybius_generate_password(data, 8, true, &mut password),
+                PolybiusStatus::InvalidArgument
+            );
+            polybius_password_data_add_number(data, 12, POLYBIUS_NUMBER_TYPE_BIRTH_MONTH);
+            assert_eq!(
+                polybius_generate_password(data, 0, true, &mut password),
+                PolybiusStatus::InvalidArgument
+            );
+            assert!(password.is_null());
+            assert_eq!(
+                polybius_generate_password(data, 8, true, ptr::null_mut()),
+                PolybiusStatus::NullPointer
+            );
+
+            assert_eq!(
+                polybius_generate_password(data, 8, true, &mut password),
+                PolybiusStatus::Ok
+            );
+            let mut buffer = [0 as c_char; 2];
+            let mut required = 0;
+            let status =
+                polybius_password_copy(password, buffer.as_mut_ptr(), buffer.len(), &mut required);
+            assert_eq!(status, PolybiusStatus::BufferTooSmall);
+            assert_eq!(required, polybius_password_len(password));
+            assert!(required > 2);
+            assert_eq!(buffer, [0, 0]);
+            polybius_password_free(password);
+
+            assert_eq!(polybius_password_len(ptr::null()), 0);
+            polybius_password_free(ptr::null_mut());
+            polybius_password_data_free(data);
+            polybius_password_data_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,59 @@
+/*
+ * Exercises the C API end to end. Built and run by tests/c_api.rs, or by hand with:
+ *     cargo build -p polybius-ffi
+ *     cc -I include tests/test.c -L ../target/debug -lpolybius_ffi -o test && LD_LIBRARY_PATH=../target/debug ./test
+ */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "polybius.h"
+
+#define CHECK(condition)                                                        \
+    do {                                                                        \
+        if (!(condition)) {                                                     \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+            return 1;                                                           \
+        }                                                                       \
+    } while (0)
+
+int main(void) {
+    PolybiusPasswordData *data = polybius_password_data_new();
+    CHECK(data != NULL);
+
+    CHECK(polybius_password_data_add_number(data, 2005, POLYBIUS_NUMBER_TYPE_BIRTH_YEAR) == POLYBIUS_STATUS_OK);
+    CHECK(polybius_password_data_add_number(data, 12, POLYBIUS_NUMBER_TYPE_BIRTH_MONTH) == POLYBIUS_STATUS_OK);
+    CHECK(polybius_password_data_add_text(data, "Cats") == POLYBIUS_STATUS_OK);
+    CHECK(polybius_password_data_add_text(data, "Lemons") == POLYBIUS_STATUS_OK);
+
+    /* Invalid arguments are reported, not crashed on */
+    CHECK(polybius_password_data_add_number(data, 1, 99) == POLYBIUS_STATUS_INVALID_ARGUMENT);
+    CHECK(polybius_password_data_add_text(data, NULL) == POLYBIUS_STATUS_NULL_POINTER);
+    CHECK(polybius_password_data_add_text(data, "\xff") == POLYBIUS_STATUS_INVALID_UTF8);
+    CHECK(polybius_password_data_add_text(NULL, "Cats") == POLYBIUS_STATUS_NULL_POINTER);
+
+    /* Ask the handle for the required size first, then copy into a buffer that fits */
+    PolybiusPassword *password = NULL;
+    CHECK(polybius_generate_password(data, 0, true, &password) == POLYBIUS_STATUS_INVALID_ARGUMENT);
+    CHECK(polybius_generate_password(data, 8, true, &password) == POLYBIUS_STATUS_OK);
+    CHECK(password != NULL);
+
+    size_t required = 0;
+    char small[1];
+    CHECK(polybius_password_copy(password, small, sizeof small, &required) == POLYBIUS_STATUS_BUFFER_TOO_SMALL);
+    CHECK(required == polybius_password_len(password));
+    CHECK(required > 8);
+
+    char *copy = malloc(required);
+    CHECK(copy != NULL);
+    CHECK(polybius_password_copy(password, copy, required, NULL) == POLYBIUS_STATUS_OK);
+    CHECK(strlen(copy) + 1 == required);
+    printf("%s\n", copy);
+    free(copy);
+
+    polybius_password_free(password);
+    polybius_password_free(NULL);
+    polybius_password_data_free(data);
+    polybius_password_data_free(NULL);
+    return 0;
+}
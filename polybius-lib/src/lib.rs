@@ -1,4 +1,34 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod password;
 pub mod password_bits;
+#[cfg(feature = "std")]
+pub mod password_breach;
+pub mod password_composition;
 pub mod password_data;
+#[cfg(feature = "std")]
+pub mod password_export;
 pub mod password_generation;
+#[cfg(feature = "std")]
+pub mod password_hints;
+pub mod password_layouts;
+pub mod password_practice;
+pub mod password_presets;
+#[cfg(feature = "profile")]
+pub mod password_profile;
+pub mod password_recipe;
+#[cfg(feature = "std")]
+pub mod password_recovery;
+pub mod password_rotation;
+pub mod password_rules;
+pub mod password_segmentation;
+#[cfg(feature = "std")]
+pub mod password_strength;
+pub mod password_typing;
+#[cfg(feature = "vault")]
+pub mod password_vault;
 pub mod prelude;
+#[cfg(feature = "wasm")]
+pub mod wasm;
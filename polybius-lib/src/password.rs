@@ -0,0 +1,246 @@
+use alloc::{string::String, vec::Vec};
+use core::{fmt, mem, ops::Range};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::password_bits::{PasswordBit, PasswordBits};
+
+/// A generated password, along with the bits it is made of
+///
+/// `Display` writes the password itself, while `Debug` keeps it hidden. The bits are wiped from memory when the
+/// password is dropped.
+#[derive(Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Password {
+    bits: PasswordBits,
+}
+
+/// How many characters of each class a password has
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct CharClasses {
+    pub lowercase: usize,
+    pub uppercase: usize,
+    pub digits: usize,
+    /// Everything that isn't a letter or a digit
+    pub symbols: usize,
+}
+
+impl CharClasses {
+    /// The number of classes used at least once
+    pub fn used(&self) -> usize {
+        [self.lowercase, self.uppercase, self.digits, self.symbols]
+            .iter()
+            .filter(|&&count| count > 0)
+            .count()
+    }
+}
+
+impl Password {
+    /// Creates a password from its bits
+    pub fn new(bits: PasswordBits) -> Self {
+        Self { bits }
+    }
+
+    /// The bits of the password, in order
+    pub fn bits(&self) -> &[PasswordBit] {
+        &self.bits
+    }
+
+    /// Takes the bits out of the password
+    pub fn into_bits(mut self) -> PasswordBits {
+        mem::take(&mut self.bits)
+    }
+
+    /// The number of bits of the password
+    pub fn bit_count(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// The length of the password, in characters
+    pub fn len(&self) -> usize {
+        self.bits.iter().map(|bit| bit.bits.chars().count()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|bit| bit.bits.is_empty())
+    }
+
+    /// The password itself, wiped from memory once dropped
+    pub fn expose_secret(&self) -> Zeroizing<String> {
+        let mut password = String::with_capacity(self.bits.iter().map(|bit| bit.bits.len()).sum());
+        for bit in &self.bits {
+            password.push_str(&bit.bits);
+        }
+        Zeroizing::new(password)
+    }
+
+    /// Counts the characters of each class in the password
+    pub fn char_classes(&self) -> CharClasses {
+        let mut classes = CharClasses::default();
+        for c in self.bits.iter().flat_map(|bit| bit.bits.chars()) {
+            if c.is_lowercase() {
+                classes.lowercase += 1;
+            } else if c.is_uppercase() {
+                classes.uppercase += 1;
+            } else if c.is_numeric() {
+                classes.digits += 1;
+            } else {
+                classes.symbols += 1;
+            }
+        }
+        classes
+    }
+
+    /// The characters each bit covers in the password, as character ranges
+    pub fn spans(&self) -> impl Iterator<Item = (Range<usize>, &PasswordBit)> {
+        let mut start = 0;
+        self.bits.iter().map(move |bit| {
+            let end = start + bit.bits.chars().count();
+            let span = start..end;
+            start = end;
+            (span, bit)
+        })
+    }
+
+    /// What each bit stands for, separated by spaces
+    pub fn explanation(&self) -> String {
+        self.bits
+            .iter()
+            .map(|bit| bit.importance.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Whether the password is `other`, compared in constant time for passwords of the same length
+    pub fn eq_secret(&self, other: &str) -> bool {
+        let password = self.expose_secret();
+        password.len() == other.len()
+            && password
+                .bytes()
+                .zip(other.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    /// Whether both passwords are the same string, even when made of different bits
+    pub fn same_password(&self, other: &Password) -> bool {
+        self.eq_secret(&other.expose_secret())
+    }
+
+    /// The number of characters to insert, delete or substitute to turn this password into `other`
+    pub fn edit_distance(&self, other: &Password) -> usize {
+        let from: Zeroizing<Vec<char>> = Zeroizing::new(self.expose_secret().chars().collect());
+        let to: Zeroizing<Vec<char>> = Zeroizing::new(other.expose_secret().chars().collect());
+
+        let mut previous: Vec<usize> = (0..=to.len()).collect();
+        let mut current = Vec::with_capacity(to.len() + 1);
+        for (i, a) in from.iter().enumerate() {
+            current.clear();
+            current.push(i + 1);
+            for (j, b) in to.iter().enumerate() {
+                let substitution = previous[j] + usize::from(a != b);
+                current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+            }
+            mem::swap(&mut previous, &mut current);
+        }
+        previous[to.len()]
+    }
+}
+
+impl From<PasswordBits> for Password {
+    fn from(bits: PasswordBits) -> Self {
+        Self::new(bits)
+    }
+}
+
+impl fmt::Display for Password {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for bit in &self.bits {
+            f.write_str(&bit.bits)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Password(<{} bits hidden>)", self.bits.len())
+    }
+}
+
+impl Drop for Password {
+    fn drop(&mut self) {
+        for bit in &mut self.bits {
+            bit.bits.zeroize();
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn password(bits: &[(&str, &str)]) -> Password {
+        Password::new(
+            bits.iter()
+                .map(|(bits, importance)| {
+                    PasswordBit::new(bits.to_string(), importance.to_string())
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_display_and_len() {
+        let password = password(&[("05", "Birth Year"), ("Cat", "Cats"), ("!", "Symbol")]);
+        assert_eq!(password.to_string(), "05Cat!");
+        assert_eq!(*password.expose_secret(), "05Cat!");
+        assert_eq!(password.len(), 6);
+        assert_eq!(password.bit_count(), 3);
+        assert!(!format!("{:?}", password).contains("Cat"));
+        assert!(Password::default().is_empty());
+    }
+
+    #[test]
+    fn test_char_classes() {
+        let classes =
+            password(&[("05", "Birth Year"), ("Cat", "Cats"), ("!", "Symbol")]).char_classes();
+        assert_eq!(
+            classes,
+            CharClasses {
+                lowercase: 2,
+                uppercase: 1,
+                digits: 2,
+                symbols: 1,
+            }
+        );
+        assert_eq!(classes.used(), 4);
+    }
+
+    #[test]
+    fn test_spans_and_explanation() {
+        let password = password(&[("05", "Birth Year"), ("Cat", "Cats"), ("!", "Symbol")]);
+        let spans: Vec<_> = password.spans().map(|(span, _)| span).collect();
+        assert_eq!(spans, vec![0..2, 2..5, 5..6]);
+        assert_eq!(password.explanation(), "Birth Year Cats Symbol");
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let first = password(&[("12", "Birth Month"), ("Le", "Lemons")]);
+        let second = password(&[("1", "Relevant Number"), ("2Le", "Other")]);
+        assert!(first.eq_secret("12Le"));
+        assert!(!first.eq_secret("12La"));
+        assert!(!first.eq_secret("12"));
+        assert!(first.same_password(&second));
+        assert_ne!(first, second);
+
+        let third = password(&[("12", "Birth Month"), ("Ca", "Cats"), ("!", "Symbol")]);
+        assert_eq!(first.edit_distance(&third), 3);
+        assert_eq!(first.edit_distance(&first), 0);
+        assert_eq!(first.edit_distance(&Password::default()), 4);
+    }
+}
@@ -1,8 +1,24 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
 use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::password_data::{Number, NumberType};
 
+/// The symbols a symbol bit can be. Based on the International Keyboard Layout top row, to be easily typed by the user.
+pub const SYMBOLS: [char; 14] = [
+    '!', '@', '#', '$', '%', '^', '&', '*', '(', ')', '-', '_', '+', '=',
+];
+
 /// A struct that represents a password bit, consisting of a two-character string and its importance to the user
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PasswordBit {
     /// The two-character string representing a password bit
     pub bits: String,
@@ -12,6 +28,40 @@ pub struct PasswordBit {
 
 pub type PasswordBits = Vec<PasswordBit>;
 
+/// Describes which entry of a `PasswordData` a password bit was made from, and how
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case", tag = "kind"))]
+pub enum BitSource {
+    /// A number from the numbers poll, written in full or truncated to its last two digits
+    Number { index: usize, truncated: bool },
+    /// The first `length` characters of a string from the text poll
+    Text { index: usize, length: usize },
+    /// A symbol from the symbol set of the generation settings, `SYMBOLS` by default
+    Symbol { index: usize },
+}
+
+/// The kinds of bits the generator can emit
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum BitCategory {
+    Number,
+    Text,
+    Symbol,
+}
+
+impl BitSource {
+    /// Returns the kind of bit this source produces
+    pub fn category(&self) -> BitCategory {
+        match self {
+            BitSource::Number { .. } => BitCategory::Number,
+            BitSource::Text { .. } => BitCategory::Text,
+            BitSource::Symbol { .. } => BitCategory::Symbol,
+        }
+    }
+}
+
 impl PasswordBit {
     /// Create a new PasswordBits instance
     pub fn new(bits: String, importance: String) -> Self {
@@ -23,21 +73,30 @@ impl PasswordBit {
     /// This function takes a `Number` as input and returns a `PasswordBit` instance
     /// with the `bits` field set to the last two digits of the number and the `importance`
     /// field set to the string representation of the number's `num_type`.
+    #[cfg(feature = "std")]
     pub fn number_bit(number: &Number) -> PasswordBit {
+        Self::number_bit_with_rng(number, &mut rand::thread_rng())
+    }
+
+    /// Create a new PasswordBit instance from a Number, using the given random number generator
+    pub fn number_bit_with_rng<R: Rng + ?Sized>(number: &Number, rng: &mut R) -> PasswordBit {
         PasswordBit {
-            bits: Self::number_to_bit(&number.value, &number.num_type),
+            bits: Self::number_to_bit(&number.value, &number.num_type, rng),
             importance: number.num_type.to_string(),
         }
     }
 
-    fn number_to_bit(number: &u16, number_type: &NumberType) -> String {
+    fn number_to_bit<R: Rng + ?Sized>(
+        number: &u16,
+        number_type: &NumberType,
+        rng: &mut R,
+    ) -> String {
         // If the number type is BirthMonth or BirthDay, we only want two digits with a leading 0 if the case.
         // If the number type is BirthYear or CurrentYear, we want to randomly choose between the full year or the last two digits.
         // If the number type is RelevantNumber, we want the full number.
         match number_type {
             NumberType::BirthMonth | NumberType::BirthDay => Self::truncate_number(number),
             NumberType::BirthYear | NumberType::CurrentYear => {
-                let mut rng = rand::thread_rng();
                 if rng.gen::<bool>() {
                     number.to_string()
                 } else {
@@ -48,11 +107,28 @@ impl PasswordBit {
         }
     }
 
+    /// Every way a number can be written as a bit, as `(truncated, bits, probability)`
+    pub(crate) fn number_forms(number: &Number) -> Vec<(bool, String, f64)> {
+        let full = number.value.to_string();
+        let truncated = Self::truncate_number(&number.value);
+        match number.num_type {
+            NumberType::BirthMonth | NumberType::BirthDay => vec![(true, truncated, 1.0)],
+            // Some years, like 12, are the same both ways
+            NumberType::BirthYear | NumberType::CurrentYear if full == truncated => {
+                vec![(false, full, 1.0)]
+            }
+            NumberType::BirthYear | NumberType::CurrentYear => {
+                vec![(false, full, 0.5), (true, truncated, 0.5)]
+            }
+            NumberType::RelevantNumber => vec![(false, full, 1.0)],
+        }
+    }
+
     /// Truncate a number to a two digits string. If less then 10, add a leading 0.
     fn truncate_number(number: &u16) -> String {
         let number = number % 100;
         if number == 0 {
-            return "00".to_string();
+            "00".to_string()
         } else if number < 10 {
             format!("0{}", number)
         } else {
@@ -61,8 +137,13 @@ impl PasswordBit {
     }
 
     /// Create a new PasswordBit instance from a String. It randomly selects one to three characters from the beginning of the string.
+    #[cfg(feature = "std")]
     pub fn string_bit(string: &str) -> PasswordBit {
-        let mut rng = rand::thread_rng();
+        Self::string_bit_with_rng(string, &mut rand::thread_rng())
+    }
+
+    /// Create a new PasswordBit instance from a String, using the given random number generator
+    pub fn string_bit_with_rng<R: Rng + ?Sized>(string: &str, rng: &mut R) -> PasswordBit {
         let bits = string.chars().take(rng.gen_range(1..4)).collect();
         PasswordBit {
             bits,
@@ -70,16 +151,31 @@ impl PasswordBit {
         }
     }
 
+    /// Every prefix of a string that can be a bit, as `(length, bits, probability)`.
+    /// Strings shorter than three characters make some of the prefixes the same, so their probabilities add up.
+    pub(crate) fn string_forms(string: &str) -> Vec<(usize, String, f64)> {
+        let mut forms: Vec<(usize, String, f64)> = Vec::new();
+        for take in 1..4 {
+            let bits: String = string.chars().take(take).collect();
+            let length = bits.chars().count();
+            match forms.iter_mut().find(|(l, _, _)| *l == length) {
+                Some(form) => form.2 += 1.0 / 3.0,
+                None => forms.push((length, bits, 1.0 / 3.0)),
+            }
+        }
+        forms
+    }
+
     /// Create a new PasswordBit instance from a symbol. It randomly selects one of the symbols from the list.
     /// The list is !, @, #, $, %, ^, &, *, (, ), -, _, +, =
+    #[cfg(feature = "std")]
     pub fn symbol_bit() -> PasswordBit {
-        let mut rng = rand::thread_rng();
+        Self::symbol_bit_with_rng(&mut rand::thread_rng())
+    }
 
-        // The default symbols to choose from. Based on the International Keyboard Layout top row. To be easily typed by the user.
-        let symbols = vec![
-            '!', '@', '#', '$', '%', '^', '&', '*', '(', ')', '-', '_', '+', '=',
-        ];
-        let bits = symbols[rng.gen_range(0..symbols.len())];
+    /// Create a new PasswordBit instance from a symbol, using the given random number generator
+    pub fn symbol_bit_with_rng<R: Rng + ?Sized>(rng: &mut R) -> PasswordBit {
+        let bits = SYMBOLS[rng.gen_range(0..SYMBOLS.len())];
         PasswordBit {
             bits: bits.to_string(),
             importance: "Symbol".to_string(),
@@ -87,7 +183,7 @@ impl PasswordBit {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -194,11 +290,41 @@ mod tests {
         assert_eq!(PasswordBit::truncate_number(&2000), "00");
     }
 
+    #[test]
+    fn test_number_forms() {
+        let forms = PasswordBit::number_forms(&Number::new(2005, NumberType::BirthYear));
+        assert_eq!(
+            forms,
+            vec![
+                (false, "2005".to_string(), 0.5),
+                (true, "05".to_string(), 0.5)
+            ]
+        );
+
+        let forms = PasswordBit::number_forms(&Number::new(12, NumberType::BirthYear));
+        assert_eq!(forms, vec![(false, "12".to_string(), 1.0)]);
+
+        let forms = PasswordBit::number_forms(&Number::new(3, NumberType::BirthDay));
+        assert_eq!(forms, vec![(true, "03".to_string(), 1.0)]);
+    }
+
+    #[test]
+    fn test_string_forms() {
+        let forms = PasswordBit::string_forms("hello");
+        let bits: Vec<&str> = forms.iter().map(|(_, bits, _)| bits.as_str()).collect();
+        assert_eq!(bits, vec!["h", "he", "hel"]);
+
+        let forms = PasswordBit::string_forms("ab");
+        assert_eq!(forms.len(), 2);
+        assert_eq!(forms[1].1, "ab");
+        assert!((forms[1].2 - 2.0 / 3.0).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_string_bit() {
         let password_bit = PasswordBit::string_bit("hello");
         assert!(
-            password_bit.bits.len() >= 1 && password_bit.bits.len() <= 3,
+            !password_bit.bits.is_empty() && password_bit.bits.len() <= 3,
             "Expected password bits to be between 1 and 3 characters, got: {}",
             password_bit.bits
         );
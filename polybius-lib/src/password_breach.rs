@@ -0,0 +1,493 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use anyhow::{bail, Context};
+use sha1::{Digest, Sha1};
+
+use crate::{
+    password::Password,
+    password_generation::{GenerationSettings, PasswordGeneration},
+};
+
+/// A local list of leaked passwords that generated passwords are checked against
+pub trait BreachCorpus {
+    /// Returns true if the password is in the corpus. A corpus that can't be read counts every password as leaked.
+    fn contains(&self, password: &str) -> bool;
+
+    /// Returns true if the password is in the corpus, or the error reading the corpus
+    fn check(&self, password: &str) -> anyhow::Result<bool> {
+        Ok(self.contains(password))
+    }
+}
+
+/// Returns the uppercase hex SHA-1 of a password, as used by Have I Been Pwned
+pub fn sha1_hex(password: &str) -> String {
+    hex::encode_upper(Sha1::digest(password.as_bytes()))
+}
+
+/// A plain wordlist of leaked passwords, one per line
+pub struct Wordlist {
+    words: HashSet<String>,
+}
+
+impl Wordlist {
+    /// Reads a wordlist, one password per line. Empty lines are skipped.
+    pub fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut words = HashSet::new();
+        for line in reader.lines() {
+            let line = line.context("Failed to read the wordlist")?;
+            let word = line.trim_end_matches('\r');
+            if !word.is_empty() {
+                words.insert(word.to_string());
+            }
+        }
+        Ok(Self { words })
+    }
+
+    /// Returns the number of passwords in the wordlist
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns true if the wordlist has no passwords
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+impl BreachCorpus for Wordlist {
+    fn contains(&self, password: &str) -> bool {
+        self.words.contains(password)
+    }
+}
+
+/// Leaked password hashes in the Have I Been Pwned format, `SHA1:COUNT` on each line, held in memory
+pub struct HibpHashes {
+    hashes: HashSet<String>,
+}
+
+impl HibpHashes {
+    /// Reads full hashes, one `SHA1:COUNT` per line
+    pub fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        Self::read("", reader)
+    }
+
+    /// Reads a range file, as returned by `range/{prefix}`, where each line is the rest of the hash after the 5 characters prefix
+    pub fn from_range(prefix: &str, reader: impl BufRead) -> anyhow::Result<Self> {
+        if prefix.len() != 5 {
+            bail!("A range prefix is 5 characters long, got {:?}", prefix);
+        }
+        Self::read(prefix, reader)
+    }
+
+    /// Adds the hashes of another range file
+    pub fn add_range(&mut self, prefix: &str, reader: impl BufRead) -> anyhow::Result<()> {
+        let range = Self::from_range(prefix, reader)?;
+        self.hashes.extend(range.hashes);
+        Ok(())
+    }
+
+    /// Returns the number of hashes
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns true if there are no hashes
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    fn read(prefix: &str, reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut hashes = HashSet::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line.context("Failed to read the hashes")?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let hash = format!("{}{}", prefix, hash_of_line(line)).to_uppercase();
+            if hash.len() != 40 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                bail!("Line {} is not a SHA-1 hash: {:?}", number + 1, line);
+            }
+            hashes.insert(hash);
+        }
+        Ok(Self { hashes })
+    }
+}
+
+impl BreachCorpus for HibpHashes {
+    fn contains(&self, password: &str) -> bool {
+        self.hashes.contains(&sha1_hex(password))
+    }
+}
+
+/// The hash part of a `SHA1:COUNT` line
+fn hash_of_line(line: &str) -> &str {
+    line.split(':').next().unwrap_or_default().trim()
+}
+
+/// A Have I Been Pwned file of full hashes sorted by hash, like `pwned-passwords-sha1-ordered-by-hash.txt`
+///
+/// The file is never loaded in memory. Every lookup is a binary search over the file offsets.
+pub struct SortedHashFile {
+    file: File,
+    length: u64,
+}
+
+impl SortedHashFile {
+    /// Opens a sorted hash file
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file = File::open(path.as_ref())
+            .with_context(|| format!("Failed to open {}", path.as_ref().display()))?;
+        let length = file.metadata()?.len();
+        Ok(Self { file, length })
+    }
+
+    /// Returns true if the SHA-1 hash, in hex, is in the file
+    pub fn contains_hash(&self, hash: &str) -> anyhow::Result<bool> {
+        let hash = hash.to_uppercase();
+        // Every line starting before `low` is smaller than the hash, and every line starting at or after `high` is bigger
+        let mut low = 0;
+        let mut high = self.length;
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let Some((line, end)) = self.line_from(middle)? else {
+                high = middle;
+                continue;
+            };
+            match hash_of_line(&line).to_uppercase().cmp(&hash) {
+                std::cmp::Ordering::Equal => return Ok(true),
+                std::cmp::Ordering::Less => low = end,
+                std::cmp::Ordering::Greater => high = middle,
+            }
+        }
+        Ok(false)
+    }
+
+    /// Reads the first line starting at or after `offset`, along with the offset of the line after it
+    fn line_from(&self, offset: u64) -> anyhow::Result<Option<(String, u64)>> {
+        let mut reader = BufReader::new(&self.file);
+        let mut start = offset;
+        if offset > 0 {
+            // Step back one byte so a line starting exactly at the offset is not skipped
+            reader.seek(SeekFrom::Start(offset - 1))?;
+            let mut skipped = Vec::new();
+            start = offset - 1 + reader.read_until(b'\n', &mut skipped)? as u64;
+        } else {
+            reader.seek(SeekFrom::Start(0))?;
+        }
+
+        let mut line = String::new();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        Ok(Some((line.trim().to_string(), start + read as u64)))
+    }
+}
+
+impl BreachCorpus for SortedHashFile {
+    fn contains(&self, password: &str) -> bool {
+        // A file that can't be read anymore can't prove a password is safe either
+        self.check(password).unwrap_or(true)
+    }
+
+    fn check(&self, password: &str) -> anyhow::Result<bool> {
+        self.contains_hash(&sha1_hex(password))
+    }
+}
+
+/// A Bloom filter of leaked passwords. It can tell a password is not leaked for sure, or that it is leaked with a small false positive rate.
+pub struct BloomFilter {
+    words: Vec<u64>,
+    bits: u64,
+    hashes: u32,
+}
+
+impl BloomFilter {
+    /// The bytes every serialized filter starts with
+    const MAGIC: &'static [u8; 8] = b"PLYBLOOM";
+    /// The version of the serialized format
+    const VERSION: u8 = 1;
+    /// The most bits a serialized filter can have, 8 GiB, far more than every Have I Been Pwned hash needs
+    const MAX_BITS: u64 = 1 << 36;
+    /// The most hashes a serialized filter can use, each lookup computing all of them
+    const MAX_HASHES: u32 = 64;
+    /// The most words reserved before they are read, so a header can't claim more memory than the file holds
+    const RESERVED_WORDS: u64 = 1 << 20;
+
+    /// Creates an empty filter sized for the expected number of passwords and false positive rate
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let items = expected_items.max(1) as f64;
+        let rate = false_positive_rate.clamp(f64::MIN_POSITIVE, 0.5);
+        let bits = (-items * rate.ln() / std::f64::consts::LN_2.powi(2)).ceil() as u64;
+        let bits = bits.max(64);
+        let hashes = ((bits as f64 / items) * std::f64::consts::LN_2).round() as u32;
+        Self {
+            words: vec![0; bits.div_ceil(64) as usize],
+            bits,
+            hashes: hashes.max(1),
+        }
+    }
+
+    /// Adds a password to the filter
+    pub fn insert(&mut self, password: &str) {
+        for index in self.indexes(password) {
+            self.words[(index / 64) as usize] |= 1 << (index % 64);
+        }
+    }
+
+    /// Writes the filter in the format read by `read_from`
+    pub fn write_to(&self, mut writer: impl Write) -> anyhow::Result<()> {
+        writer.write_all(Self::MAGIC)?;
+        writer.write_all(&[Self::VERSION])?;
+        writer.write_all(&self.hashes.to_le_bytes())?;
+        writer.write_all(&self.bits.to_le_bytes())?;
+        for word in &self.words {
+            writer.write_all(&word.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Reads a filter previously written by `write_to`
+    pub fn read_from(mut reader: impl Read) -> anyhow::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader
+            .read_exact(&mut magic)
+            .context("Failed to read the Bloom filter header")?;
+        if &magic != Self::MAGIC {
+            bail!("Not a Polybius Bloom filter");
+        }
+
+        let mut version = [0u8; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != Self::VERSION {
+            bail!("Unsupported Bloom filter version {}", version[0]);
+        }
+
+        let mut hashes = [0u8; 4];
+        reader.read_exact(&mut hashes)?;
+        let hashes = u32::from_le_bytes(hashes);
+        let mut bits = [0u8; 8];
+        reader.read_exact(&mut bits)?;
+        let bits = u64::from_le_bytes(bits);
+        if hashes == 0 || bits == 0 {
+            bail!("The Bloom filter is empty");
+        }
+        if hashes > Self::MAX_HASHES || bits > Self::MAX_BITS {
+            bail!(
+                "The Bloom filter has {} bits and {} hashes, more than the {} bits and {} hashes supported",
+                bits,
+                hashes,
+                Self::MAX_BITS,
+                Self::MAX_HASHES
+            );
+        }
+
+        let mut words = Vec::with_capacity(bits.div_ceil(64).min(Self::RESERVED_WORDS) as usize);
+        let mut word = [0u8; 8];
+        for _ in 0..bits.div_ceil(64) {
+            reader
+                .read_exact(&mut word)
+                .context("The Bloom filter is truncated")?;
+            words.push(u64::from_le_bytes(word));
+        }
+        Ok(Self {
+            words,
+            bits,
+            hashes,
+        })
+    }
+
+    /// The bit indexes of a password, using double hashing over its SHA-1
+    fn indexes(&self, password: &str) -> impl Iterator<Item = u64> {
+        let digest = Sha1::digest(password.as_bytes());
+        let first = u64::from_le_bytes(digest[0..8].try_into().unwrap());
+        let second = u64::from_le_bytes(digest[8..16].try_into().unwrap());
+        let bits = self.bits;
+        (0..self.hashes as u64).map(move |i| first.wrapping_add(i.wrapping_mul(second)) % bits)
+    }
+}
+
+impl BreachCorpus for BloomFilter {
+    fn contains(&self, password: &str) -> bool {
+        self.indexes(password)
+            .all(|index| self.words[(index / 64) as usize] & (1 << (index % 64)) != 0)
+    }
+}
+
+pub trait BreachAwareGeneration {
+    /// Generates a password that is not in the corpus
+    /// Passwords found in the corpus are thrown away and generated again, up to `max_attempts` times
+    fn generate_unbreached_password(
+        &self,
+        settings: &GenerationSettings,
+        corpus: &(impl BreachCorpus + ?Sized),
+        max_attempts: usize,
+    ) -> anyhow::Result<Password>;
+}
+
+impl<T: PasswordGeneration> BreachAwareGeneration for T {
+    fn generate_unbreached_password(
+        &self,
+        settings: &GenerationSettings,
+        corpus: &(impl BreachCorpus + ?Sized),
+        max_attempts: usize,
+    ) -> anyhow::Result<Password> {
+        for _ in 0..max_attempts {
+            let password = self.generate_password(settings);
+            if !corpus
+                .check(&password.expose_secret())
+                .context("Failed to check the password against the breach corpus")?
+            {
+                return Ok(password);
+            }
+        }
+        bail!(
+            "Every one of the {} generated passwords was found in the breach corpus",
+            max_attempts
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::password_data::{Number, NumberType, PasswordData};
+
+    use super::*;
+
+    #[test]
+    fn test_wordlist() {
+        let wordlist = Wordlist::from_reader("123456\r\npassword\n\nCat05!\n".as_bytes()).unwrap();
+        assert_eq!(wordlist.len(), 3);
+        assert!(wordlist.contains("Cat05!"));
+        assert!(wordlist.contains("123456"));
+        assert!(!wordlist.contains("cat05!"));
+    }
+
+    #[test]
+    fn test_hibp_hashes() {
+        // The SHA-1 of "password" is 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+        let hashes = HibpHashes::from_reader(
+            "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:10434004\n".as_bytes(),
+        )
+        .unwrap();
+        assert!(hashes.contains("password"));
+        assert!(!hashes.contains("Password"));
+
+        let range = HibpHashes::from_range(
+            "5baa6",
+            "1E4C9B93F3F0682250B6CF8331B7EE68FD8:3\r\n".as_bytes(),
+        )
+        .unwrap();
+        assert!(range.contains("password"));
+
+        assert!(HibpHashes::from_reader("not a hash:1".as_bytes()).is_err());
+        assert!(HibpHashes::from_range("5BAA", "".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_sorted_hash_file() {
+        let mut hashes: Vec<String> = ["password", "123456", "qwerty", "Cat05!", "letmein"]
+            .iter()
+            .map(|password| sha1_hex(password))
+            .collect();
+        hashes.sort();
+        let contents: String = hashes
+            .iter()
+            .enumerate()
+            .map(|(i, hash)| format!("{}:{}\r\n", hash, i * 1000 + 1))
+            .collect();
+
+        let path =
+            std::env::temp_dir().join(format!("polybius-sorted-hashes-{}.txt", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        let file = SortedHashFile::open(&path).unwrap();
+        for password in ["password", "123456", "qwerty", "Cat05!", "letmein"] {
+            assert!(file.contains(password), "Expected to find {}", password);
+        }
+        for password in ["", "Password", "Dog12#", "zzzzzz"] {
+            assert!(
+                !file.contains(password),
+                "Expected not to find {}",
+                password
+            );
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        // A file that can't be read is an error, and counts every password as leaked
+        let directory = std::env::temp_dir();
+        let unreadable = SortedHashFile {
+            file: File::open(&directory).unwrap(),
+            length: 100,
+        };
+        assert!(unreadable.check("Dog12#").is_err());
+        assert!(unreadable.contains("Dog12#"));
+    }
+
+    #[test]
+    fn test_bloom_filter_round_trip() {
+        let mut filter = BloomFilter::new(100, 0.001);
+        for i in 0..100 {
+            filter.insert(&format!("leaked{}", i));
+        }
+
+        let mut serialized = Vec::new();
+        filter.write_to(&mut serialized).unwrap();
+        let filter = BloomFilter::read_from(serialized.as_slice()).unwrap();
+
+        for i in 0..100 {
+            assert!(filter.contains(&format!("leaked{}", i)));
+        }
+        let false_positives = (0..1000)
+            .filter(|i| filter.contains(&format!("safe{}", i)))
+            .count();
+        assert!(
+            false_positives < 10,
+            "Got {} false positives",
+            false_positives
+        );
+
+        assert!(BloomFilter::read_from("PLYBLOOX".as_bytes()).is_err());
+        assert!(BloomFilter::read_from(&serialized[..serialized.len() - 1]).is_err());
+
+        // A header claiming more than the file holds is refused, or fails on the missing words, without allocating it
+        let header = |hashes: u32, bits: u64| {
+            let mut header = b"PLYBLOOM\x01".to_vec();
+            header.extend(hashes.to_le_bytes());
+            header.extend(bits.to_le_bytes());
+            header
+        };
+        assert!(BloomFilter::read_from(header(7, u64::MAX).as_slice()).is_err());
+        assert!(BloomFilter::read_from(header(u32::MAX, 64).as_slice()).is_err());
+        assert!(BloomFilter::read_from(header(7, 1 << 36).as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_generate_unbreached_password() {
+        let password_data = PasswordData::new(
+            [Number::new(12, NumberType::BirthMonth)].to_vec(),
+            ["a".to_string()].to_vec(),
+        );
+        let settings = GenerationSettings::builder()
+            .length(1)
+            .symbols(false)
+            .build();
+
+        // Only "a" and "12" can be generated
+        let corpus = Wordlist::from_reader("a\n".as_bytes()).unwrap();
+        let password = password_data
+            .generate_unbreached_password(&settings, &corpus, 1000)
+            .unwrap();
+        assert_eq!(password.to_string(), "12");
+
+        let corpus = Wordlist::from_reader("a\n12\n".as_bytes()).unwrap();
+        assert!(password_data
+            .generate_unbreached_password(&settings, &corpus, 10)
+            .is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! Generating around the bits a user picked, to reroll some bits of a password and keep the others
+
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+use crate::{
+    password::Password, password_bits::PasswordBit, password_data::PasswordData,
+    password_generation::GenerationSettings,
+};
+
+pub trait ComposedGeneration {
+    /// Generates a password keeping the fixed bits at their positions and generating a bit for each `None`
+    /// The password has `settings.length` bits, or as many as `fixed` when there are more of them.
+    /// The fixed bits were picked rather than drawn, and no position is skipped, so `password_strength` doesn't rate
+    /// the result: its targeted entropy only holds for the output of `generate_password`.
+    #[cfg(feature = "std")]
+    fn generate_password_with_fixed(
+        &self,
+        settings: &GenerationSettings,
+        fixed: &[Option<PasswordBit>],
+    ) -> Password {
+        self.generate_password_with_fixed_and_rng(settings, fixed, &mut rand::thread_rng())
+    }
+
+    /// Generates a password around fixed bits using the given random number generator, for targets without `std`
+    fn generate_password_with_fixed_and_rng<R: Rng + ?Sized>(
+        &self,
+        settings: &GenerationSettings,
+        fixed: &[Option<PasswordBit>],
+        rng: &mut R,
+    ) -> Password;
+
+    /// Generates the bit at `position` again, keeping the others. The password keeps its number of bits, whatever the
+    /// settings length.
+    #[cfg(feature = "std")]
+    fn reroll_bit(
+        &self,
+        password: &Password,
+        position: usize,
+        settings: &GenerationSettings,
+    ) -> Password {
+        let fixed: Vec<Option<PasswordBit>> = password
+            .bits()
+            .iter()
+            .enumerate()
+            .map(|(index, bit)| (index != position).then(|| bit.clone()))
+            .collect();
+        let mut settings = settings.clone();
+        settings.length = fixed.len();
+        self.generate_password_with_fixed(&settings, &fixed)
+    }
+}
+
+impl ComposedGeneration for PasswordData {
+    fn generate_password_with_fixed_and_rng<R: Rng + ?Sized>(
+        &self,
+        settings: &GenerationSettings,
+        fixed: &[Option<PasswordBit>],
+        rng: &mut R,
+    ) -> Password {
+        let can_generate =
+            settings.uses_symbols() || !self.numbers_poll.is_empty() || !self.text_poll.is_empty();
+
+        let length = settings.length.max(fixed.len());
+        let mut bits = Vec::with_capacity(length);
+        for position in 0..length {
+            match fixed.get(position) {
+                Some(Some(bit)) => bits.push(bit.clone()),
+                // Unlike `generate_password`, a position isn't skipped when its kind of bit has nothing to choose from
+                _ if can_generate => loop {
+                    if let Some(bit) = self.generate_bit_with_rng(settings, rng) {
+                        bits.push(bit);
+                        break;
+                    }
+                },
+                _ => {}
+            }
+        }
+        Password::new(bits)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{
+        password_data::{Number, NumberType},
+        password_generation::PasswordGeneration,
+    };
+
+    use super::*;
+
+    fn password_data() -> PasswordData {
+        PasswordData::new(
+            [Number::new(2005, NumberType::BirthYear)].to_vec(),
+            ["Cats", "Lemons"].map(String::from).to_vec(),
+        )
+    }
+
+    #[test]
+    fn test_fixed_positions() {
+        let password_data = password_data();
+        let settings = GenerationSettings::builder().length(6).build();
+        let locked = PasswordBit::new("Dog".to_string(), "Dogs".to_string());
+        let fixed = [None, Some(locked.clone()), None, None, Some(locked.clone())];
+
+        for _ in 0..20 {
+            let password = password_data.generate_password_with_fixed(&settings, &fixed);
+            assert_eq!(password.bit_count(), 6);
+            assert_eq!(password.bits()[1], locked);
+            assert_eq!(password.bits()[4], locked);
+        }
+
+        // More fixed bits than the settings length
+        let fixed = vec![Some(locked.clone()); 8];
+        assert_eq!(
+            password_data
+                .generate_password_with_fixed(&settings, &fixed)
+                .bit_count(),
+            8
+        );
+
+        // Nothing to generate from, only the fixed bits are left
+        let settings = GenerationSettings::builder().symbols(false).build();
+        let password = PasswordData::new(vec![], vec![])
+            .generate_password_with_fixed(&settings, &[None, Some(locked.clone())]);
+        assert_eq!(password.bits(), [locked]);
+    }
+
+    #[test]
+    fn test_reroll_bit() {
+        let password_data = password_data();
+        // Only numbers and texts, so every position is filled
+        let settings = GenerationSettings::builder()
+            .length(4)
+            .symbols(false)
+            .build();
+        let password = password_data.generate_password(&settings);
+        for _ in 0..20 {
+            let rerolled = password_data.reroll_bit(&password, 2, &settings);
+            assert_eq!(rerolled.bit_count(), 4);
+            for position in [0, 1, 3] {
+                assert_eq!(rerolled.bits()[position], password.bits()[position]);
+            }
+        }
+
+        // A password shorter than the settings length, like one a bit was removed from, isn't lengthened
+        let shorter = Password::new(password.bits()[..2].to_vec());
+        for _ in 0..20 {
+            let rerolled = password_data.reroll_bit(&shorter, 0, &settings);
+            assert_eq!(rerolled.bit_count(), 2);
+            assert_eq!(rerolled.bits()[1], shorter.bits()[1]);
+        }
+    }
+}
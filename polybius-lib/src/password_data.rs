@@ -1,21 +1,25 @@
-use std::fmt;
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
 
 /// Represents the types of numbers that UserData can store. These types are used to specify the significance of the numbers to the user
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub enum NumberType {
     BirthYear,
     BirthMonth,
     BirthDay,
     CurrentYear,
+    #[default]
     RelevantNumber,
 }
 
-impl Default for NumberType {
-    fn default() -> Self {
-        NumberType::RelevantNumber
-    }
-}
-
 impl fmt::Display for NumberType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -30,6 +34,7 @@ impl fmt::Display for NumberType {
 
 /// Represents a numeric value along with its type.
 #[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Number {
     /// The numeric value
     pub value: u16,
@@ -45,6 +50,8 @@ impl Number {
 }
 
 /// Contains all the information required for the password generation process.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PasswordData {
     /// A poll of positive numeric values and their importance to the user
     pub numbers_poll: Vec<Number>,
@@ -0,0 +1,367 @@
+//! Writers for the import formats of password managers, to move generated passwords into them without copying each
+//!
+//! The exports hold the passwords in plain text, like the exports of the managers themselves. Delete them once
+//! imported. XML can't hold most control characters, so the KeePass export fails on them rather than change a
+//! password.
+
+use std::io::{self, Write};
+
+use serde_json::{json, Value};
+
+use crate::password::Password;
+
+/// A generated password along with what it is for
+#[derive(Clone, PartialEq, Debug)]
+pub struct Credential {
+    /// The name of the entry, usually the site
+    pub name: String,
+    pub url: Option<String>,
+    pub username: String,
+    pub password: Password,
+    pub notes: Option<String>,
+}
+
+impl Credential {
+    pub fn new(name: &str, username: &str, password: Password) -> Self {
+        Self {
+            name: name.to_string(),
+            url: None,
+            username: username.to_string(),
+            password,
+            notes: None,
+        }
+    }
+
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    pub fn with_notes(mut self, notes: &str) -> Self {
+        self.notes = Some(notes.to_string());
+        self
+    }
+}
+
+/// The formats credentials can be exported to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    /// The unencrypted JSON export of Bitwarden
+    BitwardenJson,
+    /// The CSV KeePassXC imports and exports
+    KeePassXcCsv,
+    /// The CSV 1Password imports
+    OnePasswordCsv,
+    /// The XML export of KeePass 2
+    KeePassXml,
+}
+
+impl ExportFormat {
+    /// The usual extension of files in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::BitwardenJson => "json",
+            ExportFormat::KeePassXcCsv | ExportFormat::OnePasswordCsv => "csv",
+            ExportFormat::KeePassXml => "xml",
+        }
+    }
+}
+
+/// The group the entries are put in by the KeePass exports
+const GROUP: &str = "Polybius";
+
+/// Writes the credentials in the given format
+pub fn export_credentials(
+    credentials: &[Credential],
+    format: ExportFormat,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    match format {
+        ExportFormat::BitwardenJson => write_bitwarden_json(credentials, writer),
+        ExportFormat::KeePassXcCsv => write_keepassxc_csv(credentials, writer),
+        ExportFormat::OnePasswordCsv => write_1password_csv(credentials, writer),
+        ExportFormat::KeePassXml => write_keepass_xml(credentials, writer),
+    }
+}
+
+fn write_bitwarden_json(credentials: &[Credential], writer: &mut impl Write) -> io::Result<()> {
+    let items: Vec<Value> = credentials
+        .iter()
+        .map(|credential| {
+            let uris: Vec<Value> = credential
+                .url
+                .iter()
+                .map(|url| json!({ "match": null, "uri": url }))
+                .collect();
+            json!({
+                "type": 1,
+                "name": credential.name,
+                "notes": credential.notes,
+                "favorite": false,
+                "login": {
+                    "uris": uris,
+                    "username": credential.username,
+                    "password": *credential.password.expose_secret(),
+                    "totp": null,
+                },
+            })
+        })
+        .collect();
+    let export = json!({ "encrypted": false, "folders": [], "items": items });
+    serde_json::to_writer_pretty(&mut *writer, &export)?;
+    writeln!(writer)
+}
+
+fn write_keepassxc_csv(credentials: &[Credential], writer: &mut impl Write) -> io::Result<()> {
+    write_csv_row(
+        writer,
+        &["Group", "Title", "Username", "Password", "URL", "Notes"],
+    )?;
+    for credential in credentials {
+        write_csv_row(
+            writer,
+            &[
+                GROUP,
+                &credential.name,
+                &credential.username,
+                &credential.password.expose_secret(),
+                credential.url.as_deref().unwrap_or_default(),
+                credential.notes.as_deref().unwrap_or_default(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn write_1password_csv(credentials: &[Credential], writer: &mut impl Write) -> io::Result<()> {
+    write_csv_row(
+        writer,
+        &["Title", "Website", "Username", "Password", "Notes"],
+    )?;
+    for credential in credentials {
+        write_csv_row(
+            writer,
+            &[
+                &credential.name,
+                credential.url.as_deref().unwrap_or_default(),
+                &credential.username,
+                &credential.password.expose_secret(),
+                credential.notes.as_deref().unwrap_or_default(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn write_keepass_xml(credentials: &[Credential], writer: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        writer,
+        "<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"yes\"?>"
+    )?;
+    writeln!(writer, "<KeePassFile>")?;
+    writeln!(writer, "\t<Root>")?;
+    writeln!(writer, "\t\t<Group>")?;
+    writeln!(writer, "\t\t\t<Name>{}</Name>", GROUP)?;
+    for credential in credentials {
+        writeln!(writer, "\t\t\t<Entry>")?;
+        write_xml_string(writer, "Title", &credential.name, false)?;
+        write_xml_string(writer, "UserName", &credential.username, false)?;
+        write_xml_string(
+            writer,
+            "Password",
+            &credential.password.expose_secret(),
+            true,
+        )?;
+        write_xml_string(
+            writer,
+            "URL",
+            credential.url.as_deref().unwrap_or_default(),
+            false,
+        )?;
+        write_xml_string(
+            writer,
+            "Notes",
+            credential.notes.as_deref().unwrap_or_default(),
+            false,
+        )?;
+        writeln!(writer, "\t\t\t</Entry>")?;
+    }
+    writeln!(writer, "\t\t</Group>")?;
+    writeln!(writer, "\t</Root>")?;
+    writeln!(writer, "</KeePassFile>")
+}
+
+fn write_xml_string(
+    writer: &mut impl Write,
+    key: &str,
+    value: &str,
+    protected: bool,
+) -> io::Result<()> {
+    let protection = if protected {
+        " ProtectInMemory=\"True\""
+    } else {
+        ""
+    };
+    writeln!(
+        writer,
+        "\t\t\t\t<String><Key>{}</Key><Value{}>{}</Value></String>",
+        key,
+        protection,
+        xml_escape(value)?
+    )
+}
+
+/// Writes a CSV row, quoting every field so commas, quotes and line breaks survive
+fn write_csv_row(writer: &mut impl Write, fields: &[&str]) -> io::Result<()> {
+    let row: Vec<String> = fields
+        .iter()
+        .map(|field| format!("\"{}\"", field.replace('"', "\"\"")))
+        .collect();
+    write!(writer, "{}\r\n", row.join(","))
+}
+
+/// Escapes a value for XML text, keeping carriage returns that parsers would otherwise turn into line feeds
+fn xml_escape(value: &str) -> io::Result<String> {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' | '\n' => escaped.push(c),
+            // Not allowed in XML 1.0, even as character references
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("XML can't hold the character U+{:04X}", c as u32),
+                ))
+            }
+            c => escaped.push(c),
+        }
+    }
+    Ok(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::password_bits::PasswordBit;
+
+    use super::*;
+
+    fn credentials() -> Vec<Credential> {
+        let password = |bits: &[&str]| {
+            Password::new(
+                bits.iter()
+                    .map(|bits| PasswordBit::new(bits.to_string(), "Test".to_string()))
+                    .collect(),
+            )
+        };
+        vec![
+            Credential::new(
+                "Mail",
+                "me@example.com",
+                password(&["05", "Cat", "\"", ","]),
+            )
+            .with_url("https://mail.example.com")
+            .with_notes("Line one\nLine \"two\""),
+            Credential::new("Bank <old>", "me", password(&["&", "Le", "12", "<"]))
+                .with_notes("Ring\u{7}\r\n"),
+        ]
+    }
+
+    fn export(format: ExportFormat) -> String {
+        let mut output = Vec::new();
+        export_credentials(&credentials(), format, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_bitwarden_json() {
+        let json: Value = serde_json::from_str(&export(ExportFormat::BitwardenJson)).unwrap();
+        assert_eq!(json["encrypted"], false);
+        let items = json["items"].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["name"], "Mail");
+        assert_eq!(items[0]["notes"], "Line one\nLine \"two\"");
+        assert_eq!(items[0]["login"]["username"], "me@example.com");
+        assert_eq!(items[0]["login"]["password"], "05Cat\",");
+        assert_eq!(
+            items[0]["login"]["uris"][0]["uri"],
+            "https://mail.example.com"
+        );
+        assert_eq!(items[1]["notes"], "Ring\u{7}\r\n");
+        assert_eq!(items[1]["login"]["password"], "&Le12<");
+    }
+
+    #[test]
+    fn test_keepassxc_csv() {
+        assert_eq!(
+            export(ExportFormat::KeePassXcCsv),
+            concat!(
+                "\"Group\",\"Title\",\"Username\",\"Password\",\"URL\",\"Notes\"\r\n",
+                "\"Polybius\",\"Mail\",\"me@example.com\",\"05Cat\"\",\",\"https://mail.example.com\",",
+                "\"Line one\nLine \"\"two\"\"\"\r\n",
+                "\"Polybius\",\"Bank <old>\",\"me\",\"&Le12<\",\"\",\"Ring\u{7}\r\n\"\r\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_1password_csv() {
+        assert_eq!(
+            export(ExportFormat::OnePasswordCsv),
+            concat!(
+                "\"Title\",\"Website\",\"Username\",\"Password\",\"Notes\"\r\n",
+                "\"Mail\",\"https://mail.example.com\",\"me@example.com\",\"05Cat\"\",\",",
+                "\"Line one\nLine \"\"two\"\"\"\r\n",
+                "\"Bank <old>\",\"\",\"me\",\"&Le12<\",\"Ring\u{7}\r\n\"\r\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_keepass_xml() {
+        let mut credentials = credentials();
+        credentials[1].notes = Some("Tab\tand 'return'\r\n".to_string());
+        let mut output = Vec::new();
+        export_credentials(&credentials, ExportFormat::KeePassXml, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"yes\"?>\n",
+                "<KeePassFile>\n",
+                "\t<Root>\n",
+                "\t\t<Group>\n",
+                "\t\t\t<Name>Polybius</Name>\n",
+                "\t\t\t<Entry>\n",
+                "\t\t\t\t<String><Key>Title</Key><Value>Mail</Value></String>\n",
+                "\t\t\t\t<String><Key>UserName</Key><Value>me@example.com</Value></String>\n",
+                "\t\t\t\t<String><Key>Password</Key><Value ProtectInMemory=\"True\">05Cat&quot;,</Value></String>\n",
+                "\t\t\t\t<String><Key>URL</Key><Value>https://mail.example.com</Value></String>\n",
+                "\t\t\t\t<String><Key>Notes</Key><Value>Line one\nLine &quot;two&quot;</Value></String>\n",
+                "\t\t\t</Entry>\n",
+                "\t\t\t<Entry>\n",
+                "\t\t\t\t<String><Key>Title</Key><Value>Bank &lt;old&gt;</Value></String>\n",
+                "\t\t\t\t<String><Key>UserName</Key><Value>me</Value></String>\n",
+                "\t\t\t\t<String><Key>Password</Key><Value ProtectInMemory=\"True\">&amp;Le12&lt;</Value></String>\n",
+                "\t\t\t\t<String><Key>URL</Key><Value></Value></String>\n",
+                "\t\t\t\t<String><Key>Notes</Key><Value>Tab\tand &apos;return&apos;&#13;\n</Value></String>\n",
+                "\t\t\t</Entry>\n",
+                "\t\t</Group>\n",
+                "\t</Root>\n",
+                "</KeePassFile>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_keepass_xml_control_characters() {
+        let error = export_credentials(&credentials(), ExportFormat::KeePassXml, &mut Vec::new())
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "XML can't hold the character U+0007");
+    }
+}
@@ -1,61 +1,261 @@
+use alloc::{string::ToString, vec::Vec};
+use core::fmt;
+
 use rand::Rng;
 
 use crate::{
-    password_bits::{PasswordBit, PasswordBits},
+    password::Password,
+    password_bits::{BitSource, PasswordBit, PasswordBits, SYMBOLS},
     password_data::PasswordData,
 };
 use rand::seq::SliceRandom; // Import the SliceRandom trait
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
+/// The settings of the password generation. Build them with `GenerationSettings::builder()`
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
 pub struct GenerationSettings {
+    /// The number of bits in the password
     pub length: usize,
+    /// Whether symbols can be used as bits
     pub symbols: bool,
+    /// The symbols a symbol bit can be, `SYMBOLS` by default
+    pub symbol_set: Vec<char>,
+}
+
+impl Default for GenerationSettings {
+    /// The settings used by the web app, 8 bits with symbols
+    fn default() -> Self {
+        Self {
+            length: 8,
+            symbols: true,
+            symbol_set: SYMBOLS.to_vec(),
+        }
+    }
+}
+
+/// Builds `GenerationSettings`, starting from the defaults
+#[derive(Clone, Debug, Default)]
+pub struct GenerationSettingsBuilder {
+    settings: GenerationSettings,
+}
+
+impl GenerationSettingsBuilder {
+    /// Sets the number of bits in the password
+    pub fn length(mut self, length: usize) -> Self {
+        self.settings.length = length;
+        self
+    }
+
+    /// Sets whether symbols can be used as bits
+    pub fn symbols(mut self, symbols: bool) -> Self {
+        self.settings.symbols = symbols;
+        self
+    }
+
+    /// Sets the symbols a symbol bit can be. An empty set disables symbols.
+    pub fn symbol_set(mut self, symbol_set: impl IntoIterator<Item = char>) -> Self {
+        self.settings.symbol_set = symbol_set.into_iter().collect();
+        self
+    }
+
+    pub fn build(self) -> GenerationSettings {
+        self.settings
+    }
+}
+
+/// The reasons settings can't generate a password from some data
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum SettingsError {
+    /// The password would have no bits
+    ZeroLength,
+    /// The data has no numbers nor texts, so most or all bits would be skipped
+    NothingToGenerate,
+    /// The numbers poll is empty, so the positions drawn as numbers would be skipped and the password cut short
+    NoNumbers,
+    /// The text poll is empty, so the positions drawn as texts would be skipped and the password cut short
+    NoTexts,
+    /// The text at this index of the text poll is empty and would generate empty bits
+    EmptyText(usize),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SettingsError::ZeroLength => write!(f, "The password length must be at least 1"),
+            SettingsError::NothingToGenerate => write!(
+                f,
+                "There are no numbers or texts to generate the password from"
+            ),
+            SettingsError::NoNumbers => write!(f, "There must be at least one number"),
+            SettingsError::NoTexts => write!(f, "There must be at least one text"),
+            SettingsError::EmptyText(index) => write!(f, "The text number {} is empty", index + 1),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SettingsError {}
+
+/// A bit the generator can emit, along with the probability of emitting it at a given position
+pub(crate) struct Emission {
+    pub bit: PasswordBit,
+    pub source: BitSource,
+    pub probability: f64,
+}
+
+impl GenerationSettings {
+    /// Starts building settings from the defaults
+    pub fn builder() -> GenerationSettingsBuilder {
+        GenerationSettingsBuilder::default()
+    }
+
+    /// Checks these settings can generate a password from the data
+    pub fn validate(&self, data: &PasswordData) -> Result<(), SettingsError> {
+        if self.length == 0 {
+            return Err(SettingsError::ZeroLength);
+        }
+        if let Some(index) = data.text_poll.iter().position(|text| text.is_empty()) {
+            return Err(SettingsError::EmptyText(index));
+        }
+        // Every category with odds must have something to choose from, or the password would be shorter than asked
+        match (data.numbers_poll.is_empty(), data.text_poll.is_empty()) {
+            (true, true) => Err(SettingsError::NothingToGenerate),
+            (true, false) => Err(SettingsError::NoNumbers),
+            (false, true) => Err(SettingsError::NoTexts),
+            (false, false) => Ok(()),
+        }
+    }
+
+    /// Whether symbol bits can be generated, symbols being enabled and the symbol set not empty
+    pub fn uses_symbols(&self) -> bool {
+        self.symbols && !self.symbol_set.is_empty()
+    }
+
+    /// The odds of each position being a symbol, a number or a text, in this order. Mirrors `generate_password`.
+    pub(crate) fn category_odds(&self) -> (f64, f64, f64) {
+        if self.uses_symbols() {
+            (0.25, 0.25, 0.5)
+        } else {
+            (0.0, 1.0 / 3.0, 2.0 / 3.0)
+        }
+    }
+}
+
+impl PasswordData {
+    /// Lists every bit the generator can emit for this data, following the same odds as `generate_password`
+    pub(crate) fn emissions(&self, settings: &GenerationSettings) -> Vec<Emission> {
+        let (symbol_odds, number_odds, text_odds) = settings.category_odds();
+        let mut emissions = Vec::new();
+
+        if settings.uses_symbols() {
+            for (index, symbol) in settings.symbol_set.iter().enumerate() {
+                emissions.push(Emission {
+                    bit: PasswordBit::new(symbol.to_string(), "Symbol".to_string()),
+                    source: BitSource::Symbol { index },
+                    probability: symbol_odds / settings.symbol_set.len() as f64,
+                });
+            }
+        }
+
+        for (index, number) in self.numbers_poll.iter().enumerate() {
+            for (truncated, bits, probability) in PasswordBit::number_forms(number) {
+                emissions.push(Emission {
+                    bit: PasswordBit::new(bits, number.num_type.to_string()),
+                    source: BitSource::Number { index, truncated },
+                    probability: number_odds / self.numbers_poll.len() as f64 * probability,
+                });
+            }
+        }
+
+        for (index, text) in self.text_poll.iter().enumerate() {
+            for (length, bits, probability) in PasswordBit::string_forms(text) {
+                emissions.push(Emission {
+                    bit: PasswordBit::new(bits, text.to_string()),
+                    source: BitSource::Text { index, length },
+                    probability: text_odds / self.text_poll.len() as f64 * probability,
+                });
+            }
+        }
+
+        emissions
+    }
 }
 
 pub trait PasswordGeneration {
     /// Generates a password
     /// The password is generated by concatenating random numeric values in the numbers_poll and random acronyms of the strings in the text_poll with optionals symbols
-    fn generate_password(&self, settings: &GenerationSettings) -> PasswordBits;
+    #[cfg(feature = "std")]
+    fn generate_password(&self, settings: &GenerationSettings) -> Password {
+        self.generate_password_with_rng(settings, &mut rand::thread_rng())
+    }
+
+    /// Generates a password using the given random number generator, for targets without `std`
+    fn generate_password_with_rng<R: Rng + ?Sized>(
+        &self,
+        settings: &GenerationSettings,
+        rng: &mut R,
+    ) -> Password;
 }
 
 impl PasswordGeneration for PasswordData {
-    fn generate_password(&self, settings: &GenerationSettings) -> PasswordBits {
+    fn generate_password_with_rng<R: Rng + ?Sized>(
+        &self,
+        settings: &GenerationSettings,
+        rng: &mut R,
+    ) -> Password {
         let mut bits: PasswordBits = Vec::new();
-        let mut rng = rand::thread_rng();
 
+        // Choose at randomly some numbers and some strings from the polls and convert them to PasswordBits
+        for _ in 0..settings.length {
+            if let Some(bit) = self.generate_bit_with_rng(settings, rng) {
+                bits.push(bit);
+            }
+        }
+
+        Password::new(bits)
+    }
+}
+
+impl PasswordData {
+    /// Generates a single bit, the way `generate_password` does for each position. None when the kind of bit chosen
+    /// has nothing to choose from, like a number when the numbers poll is empty.
+    pub fn generate_bit_with_rng<R: Rng + ?Sized>(
+        &self,
+        settings: &GenerationSettings,
+        rng: &mut R,
+    ) -> Option<PasswordBit> {
         let generation_range = {
-            if settings.symbols {
+            if settings.uses_symbols() {
                 0..=3
             } else {
                 1..=3
             }
         };
 
-        // Choose at randomly some numbers and some strings from the polls and convert them to PasswordBits
-        for _ in 0..settings.length {
-            match rng.gen_range(generation_range.to_owned()) {
-                0 => {
-                    bits.push(PasswordBit::symbol_bit());
-                }
-                1 => {
-                    if let Some(number) = self.numbers_poll.choose(&mut rng) {
-                        bits.push(PasswordBit::number_bit(number));
-                    }
-                }
-                // Increase the probability of choosing a string
-                _ => {
-                    if let Some(text) = self.text_poll.choose(&mut rng) {
-                        bits.push(PasswordBit::string_bit(text));
-                    }
-                }
-            }
+        match rng.gen_range(generation_range) {
+            0 => settings
+                .symbol_set
+                .choose(rng)
+                .map(|symbol| PasswordBit::new(symbol.to_string(), "Symbol".to_string())),
+            1 => self
+                .numbers_poll
+                .choose(rng)
+                .map(|number| PasswordBit::number_bit_with_rng(number, rng)),
+            // Increase the probability of choosing a string
+            _ => self
+                .text_poll
+                .choose(rng)
+                .map(|text| PasswordBit::string_bit_with_rng(text, rng)),
         }
-
-        // Concatenate the bits to form the password
-        bits
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use crate::password_data::{Number, NumberType};
 
@@ -67,12 +267,36 @@ mod tests {
             [Number::new(1, NumberType::RelevantNumber)].to_vec(),
             ["a".to_string()].to_vec(),
         );
-        let generation_settings = GenerationSettings {
-            length: 0,
-            symbols: true,
-        };
-        let password_bits = password_data.generate_password(&generation_settings);
-        assert_eq!(password_bits.len(), 0);
+        let generation_settings = GenerationSettings::builder()
+            .length(0)
+            .symbols(true)
+            .build();
+        let password = password_data.generate_password(&generation_settings);
+        assert_eq!(password.bit_count(), 0);
+    }
+
+    #[test]
+    fn test_emissions_add_up() {
+        let password_data = PasswordData::new(
+            [
+                Number::new(2005, NumberType::BirthYear),
+                Number::new(12, NumberType::BirthMonth),
+            ]
+            .to_vec(),
+            ["Cats".to_string(), "ab".to_string()].to_vec(),
+        );
+        for symbols in [true, false] {
+            let settings = GenerationSettings::builder()
+                .length(8)
+                .symbols(symbols)
+                .build();
+            let total: f64 = password_data
+                .emissions(&settings)
+                .iter()
+                .map(|e| e.probability)
+                .sum();
+            assert!((total - 1.0).abs() < 1e-9, "Expected 1, got: {}", total);
+        }
     }
 
     #[test]
@@ -81,19 +305,106 @@ mod tests {
             [Number::new(1, NumberType::RelevantNumber)].to_vec(),
             ["a".to_string()].to_vec(),
         );
-        let generation_settings = GenerationSettings {
-            length: 50, // Increase the length to make the test more reliable
-            symbols: false,
-        };
+        let generation_settings = GenerationSettings::builder()
+            .length(50) // Increase the length to make the test more reliable
+            .symbols(false)
+            .build();
         let password_bits = password_data.generate_password(&generation_settings);
         let symbols = vec![
             "!", "@", "#", "$", "%", "^", "&", "*", "(", ")", "-", "_", "+", "=",
         ];
 
-        for bit in password_bits {
+        for bit in password_bits.bits() {
             if symbols.contains(&bit.bits.as_str()) {
                 panic!("Found symbol: {}", bit.bits);
             }
         }
     }
+
+    #[test]
+    fn test_generate_password_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let password_data = PasswordData::new(
+            [Number::new(2005, NumberType::BirthYear)].to_vec(),
+            ["Cats".to_string(), "Lemons".to_string()].to_vec(),
+        );
+        let generation_settings = GenerationSettings::builder()
+            .length(8)
+            .symbols(true)
+            .build();
+        let first = password_data
+            .generate_password_with_rng(&generation_settings, &mut StdRng::seed_from_u64(42));
+        let second = password_data
+            .generate_password_with_rng(&generation_settings, &mut StdRng::seed_from_u64(42));
+        assert_eq!(first.bit_count(), 8);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_settings_builder() {
+        assert_eq!(
+            GenerationSettings::builder().build(),
+            GenerationSettings::default()
+        );
+        let settings = GenerationSettings::builder()
+            .length(12)
+            .symbols(false)
+            .build();
+        assert_eq!(settings.length, 12);
+        assert!(!settings.symbols);
+    }
+
+    #[test]
+    fn test_settings_validate() {
+        let password_data = PasswordData::new(
+            [Number::new(12, NumberType::BirthMonth)].to_vec(),
+            ["Cats".to_string()].to_vec(),
+        );
+        let settings = GenerationSettings::default();
+        assert_eq!(settings.validate(&password_data), Ok(()));
+        assert_eq!(
+            GenerationSettings::builder()
+                .length(0)
+                .build()
+                .validate(&password_data),
+            Err(SettingsError::ZeroLength)
+        );
+
+        let empty = PasswordData::default();
+        assert_eq!(
+            settings.validate(&empty),
+            Err(SettingsError::NothingToGenerate)
+        );
+        assert_eq!(
+            GenerationSettings::builder()
+                .symbols(false)
+                .build()
+                .validate(&empty),
+            Err(SettingsError::NothingToGenerate)
+        );
+        assert_eq!(
+            settings.validate(&PasswordData::new(
+                Vec::new(),
+                ["Cats".to_string()].to_vec()
+            )),
+            Err(SettingsError::NoNumbers)
+        );
+        assert_eq!(
+            settings.validate(&PasswordData::new(
+                [Number::new(12, NumberType::BirthMonth)].to_vec(),
+                Vec::new()
+            )),
+            Err(SettingsError::NoTexts)
+        );
+
+        let password_data = PasswordData::new(
+            [Number::new(12, NumberType::BirthMonth)].to_vec(),
+            ["Cats".to_string(), "".to_string()].to_vec(),
+        );
+        assert_eq!(
+            settings.validate(&password_data),
+            Err(SettingsError::EmptyText(1))
+        );
+    }
 }
@@ -0,0 +1,334 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    password::Password,
+    password_bits::{BitSource, PasswordBit},
+    password_data::{NumberType, PasswordData},
+    password_generation::{Emission, GenerationSettings},
+};
+
+/// Hints leaving less than this many bits of entropy to an attacker that knows the data are refused by default
+pub const DEFAULT_MIN_HINT_ENTROPY: f64 = 20.0;
+
+/// How much a hint tells about each bit, from the least to the most
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub enum DisclosureLevel {
+    /// Only whether the bit is a number, a word or a symbol, like `[Number]`
+    #[default]
+    Category,
+    /// The type of the number or the label of the word, like `[Birth Year]` or `[Pet name]`
+    Kind,
+    /// The kind and how long the bit is, like `[Birth Year, short]` or `[Pet name, 3 chars]`
+    KindAndLength,
+}
+
+/// The settings of the recall hints
+#[derive(Clone, PartialEq, Debug)]
+pub struct HintSettings {
+    pub level: DisclosureLevel,
+    /// What the texts of the text poll are, like `Cats` being a `Pet name`. Texts without a label are a `Word`.
+    pub text_labels: HashMap<String, String>,
+    /// The fewest bits of entropy the hint must leave to an attacker that knows the data
+    pub min_entropy: f64,
+}
+
+impl Default for HintSettings {
+    fn default() -> Self {
+        Self {
+            level: DisclosureLevel::default(),
+            text_labels: HashMap::new(),
+            min_entropy: DEFAULT_MIN_HINT_ENTROPY,
+        }
+    }
+}
+
+/// A hint to remember a password by, safe to write down
+#[derive(Clone, PartialEq, Debug)]
+pub struct Hint {
+    /// The description of each bit, in order
+    pub parts: Vec<String>,
+    /// The bits of entropy left to an attacker that knows both the data and the hint
+    pub remaining_entropy: f64,
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = self
+            .parts
+            .iter()
+            .map(|part| format!("[{}]", part))
+            .collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// The reasons a hint is not given
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum HintError {
+    /// The bit at this position can't be made from the data
+    UnknownBit(usize),
+    /// The hint would leave too little entropy to an attacker that knows the data
+    TooRevealing {
+        remaining_entropy: f64,
+        min_entropy: f64,
+    },
+}
+
+impl fmt::Display for HintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HintError::UnknownBit(position) => write!(
+                f,
+                "The bit number {} can't be made from the data",
+                position + 1
+            ),
+            HintError::TooRevealing {
+                remaining_entropy,
+                min_entropy,
+            } => write!(
+                f,
+                "The hint leaves {:.1} bits of entropy, less than the {:.1} required",
+                remaining_entropy, min_entropy
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HintError {}
+
+pub trait PasswordHints {
+    /// Describes each bit of a password at the level of the settings, refusing hints that leave an attacker knowing
+    /// the data and the hint less than `min_entropy` bits of entropy
+    fn recall_hint(
+        &self,
+        password: &Password,
+        settings: &GenerationSettings,
+        hint_settings: &HintSettings,
+    ) -> Result<Hint, HintError>;
+}
+
+impl PasswordHints for PasswordData {
+    fn recall_hint(
+        &self,
+        password: &Password,
+        settings: &GenerationSettings,
+        hint_settings: &HintSettings,
+    ) -> Result<Hint, HintError> {
+        let emissions = self.emissions(settings);
+        let describe = |e: &Emission| describe(self, &e.source, &e.bit, hint_settings);
+
+        let mut parts = Vec::with_capacity(password.bit_count());
+        let mut remaining_entropy = 0.0;
+        for (position, bit) in password.bits().iter().enumerate() {
+            let source = emissions
+                .iter()
+                .filter(|e| e.bit == *bit)
+                .max_by(|a, b| a.probability.total_cmp(&b.probability))
+                .ok_or(HintError::UnknownBit(position))?;
+            let part = describe(source);
+
+            // Among the bits matching the hint, how likely the attacker is to guess this one
+            let mut hinted = 0.0;
+            let mut guessed = 0.0;
+            for emission in emissions.iter().filter(|e| describe(e) == part) {
+                hinted += emission.probability;
+                if emission.bit.bits == bit.bits {
+                    guessed += emission.probability;
+                }
+            }
+            remaining_entropy -= (guessed / hinted).log2();
+            parts.push(part);
+        }
+
+        if remaining_entropy < hint_settings.min_entropy {
+            return Err(HintError::TooRevealing {
+                remaining_entropy,
+                min_entropy: hint_settings.min_entropy,
+            });
+        }
+        Ok(Hint {
+            parts,
+            remaining_entropy,
+        })
+    }
+}
+
+/// Describes a bit at the level of the settings
+fn describe(
+    data: &PasswordData,
+    source: &BitSource,
+    bit: &PasswordBit,
+    hint_settings: &HintSettings,
+) -> String {
+    let level = hint_settings.level;
+    let length = bit.bits.chars().count();
+    match *source {
+        BitSource::Symbol { .. } => "Symbol".to_string(),
+        _ if level == DisclosureLevel::Category => match source {
+            BitSource::Number { .. } => "Number".to_string(),
+            _ => "Word".to_string(),
+        },
+        BitSource::Number { index, truncated } => {
+            let num_type = data.numbers_poll[index].num_type;
+            if level == DisclosureLevel::Kind {
+                return num_type.to_string();
+            }
+            match num_type {
+                NumberType::BirthYear | NumberType::CurrentYear if truncated => {
+                    format!("{}, short", num_type)
+                }
+                NumberType::BirthYear | NumberType::CurrentYear => format!("{}, full", num_type),
+                _ => format!("{}, {} digits", num_type, length),
+            }
+        }
+        BitSource::Text { index, .. } => {
+            let label = hint_settings
+                .text_labels
+                .get(&data.text_poll[index])
+                .map_or("Word", String::as_str);
+            match (level, length) {
+                (DisclosureLevel::Kind, _) => label.to_string(),
+                (_, 1) => format!("{}, initial", label),
+                _ => format!("{}, {} chars", label, length),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::password_data::Number;
+
+    use super::*;
+
+    fn data() -> PasswordData {
+        PasswordData::new(
+            [
+                Number::new(2005, NumberType::BirthYear),
+                Number::new(12, NumberType::BirthMonth),
+                Number::new(14, NumberType::BirthDay),
+                Number::new(2024, NumberType::CurrentYear),
+            ]
+            .to_vec(),
+            [
+                "Whiskers", "Rex", "Apples", "Bananas", "Oranges", "Lemons", "Paris", "Lover",
+            ]
+            .map(String::from)
+            .to_vec(),
+        )
+    }
+
+    fn password() -> Password {
+        Password::new(
+            [
+                ("Whi", "Whiskers"),
+                ("05", "Birth Year"),
+                ("!", "Symbol"),
+                ("A", "Apples"),
+                ("Pa", "Paris"),
+                ("14", "Birth Day"),
+                ("Ba", "Bananas"),
+                ("Lo", "Lover"),
+            ]
+            .iter()
+            .map(|(bits, importance)| PasswordBit::new(bits.to_string(), importance.to_string()))
+            .collect(),
+        )
+    }
+
+    fn hint_settings(level: DisclosureLevel, min_entropy: f64) -> HintSettings {
+        HintSettings {
+            level,
+            text_labels: [
+                ("Whiskers", "Pet name"),
+                ("Rex", "Pet name"),
+                ("Apples", "Fruit"),
+                ("Bananas", "Fruit"),
+                ("Oranges", "Fruit"),
+                ("Lemons", "Fruit"),
+            ]
+            .iter()
+            .map(|(text, label)| (text.to_string(), label.to_string()))
+            .collect(),
+            min_entropy,
+        }
+    }
+
+    #[test]
+    fn test_hint_levels() {
+        let settings = GenerationSettings::default();
+        let hint = |level| {
+            data()
+                .recall_hint(&password(), &settings, &hint_settings(level, 0.0))
+                .unwrap()
+        };
+
+        let category = hint(DisclosureLevel::Category);
+        assert_eq!(
+            category.to_string(),
+            "[Word] [Number] [Symbol] [Word] [Word] [Number] [Word] [Word]"
+        );
+        let kind = hint(DisclosureLevel::Kind);
+        assert_eq!(
+            kind.to_string(),
+            "[Pet name] [Birth Year] [Symbol] [Fruit] [Word] [Birth Day] [Fruit] [Word]"
+        );
+        let length = hint(DisclosureLevel::KindAndLength);
+        assert_eq!(
+            length.to_string(),
+            "[Pet name, 3 chars] [Birth Year, short] [Symbol] [Fruit, initial] [Word, 2 chars] [Birth Day, 2 digits] [Fruit, 2 chars] [Word, 2 chars]"
+        );
+        assert!(!length.to_string().contains("Whi"));
+
+        // Each level tells more, so leaves less entropy
+        assert!(category.remaining_entropy > kind.remaining_entropy);
+        assert!(kind.remaining_entropy > length.remaining_entropy);
+        assert!(length.remaining_entropy > 0.0);
+    }
+
+    #[test]
+    fn test_refuses_revealing_hints() {
+        let settings = GenerationSettings::default();
+        let threshold = data()
+            .recall_hint(
+                &password(),
+                &settings,
+                &hint_settings(DisclosureLevel::Kind, 0.0),
+            )
+            .unwrap()
+            .remaining_entropy;
+
+        assert!(data()
+            .recall_hint(
+                &password(),
+                &settings,
+                &hint_settings(DisclosureLevel::Category, threshold)
+            )
+            .is_ok());
+        assert!(matches!(
+            data().recall_hint(
+                &password(),
+                &settings,
+                &hint_settings(DisclosureLevel::KindAndLength, threshold)
+            ),
+            Err(HintError::TooRevealing { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unknown_bit() {
+        let password = Password::new(vec![PasswordBit::new(
+            "Dog".to_string(),
+            "Dogs".to_string(),
+        )]);
+        assert_eq!(
+            data().recall_hint(
+                &password,
+                &GenerationSettings::default(),
+                &HintSettings::default()
+            ),
+            Err(HintError::UnknownBit(0))
+        );
+    }
+}
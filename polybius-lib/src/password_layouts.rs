@@ -0,0 +1,160 @@
+//! Computer keyboard layouts, to only use symbols typable without AltGr
+//!
+//! `SYMBOLS` follows the top row of the US layout, but some of them need AltGr elsewhere, like `@` and `#` on a French
+//! keyboard. Dead keys, like `^` on a German keyboard, are left out too as they wait for the next key.
+
+use alloc::vec::Vec;
+use core::{fmt, str::FromStr};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::password_generation::GenerationSettings;
+
+/// A computer keyboard layout
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum KeyboardLayout {
+    Us,
+    Uk,
+    /// German QWERTZ
+    De,
+    /// French AZERTY
+    Fr,
+    /// Romanian standard
+    Ro,
+}
+
+impl KeyboardLayout {
+    pub const ALL: [KeyboardLayout; 5] = [
+        KeyboardLayout::Us,
+        KeyboardLayout::Uk,
+        KeyboardLayout::De,
+        KeyboardLayout::Fr,
+        KeyboardLayout::Ro,
+    ];
+
+    /// The symbols typable without AltGr, with or without Shift
+    pub fn symbols(&self) -> &'static str {
+        match self {
+            KeyboardLayout::Us => "`~!@#$%^&*()-_=+[]{}\\|;:'\",<.>/?",
+            KeyboardLayout::Uk => "`¬!\"£$%^&*()-_=+[]{};:'@#~\\|,<.>/?",
+            KeyboardLayout::De => "°!\"§$%&/()=?+*#',;.:-_<>",
+            KeyboardLayout::Fr => "²&\"'(-_)=°+$£%*µ,?;.:/!§<>",
+            KeyboardLayout::Ro => "!@#$%^&*()-_=+,;.:/?„”",
+        }
+    }
+
+    /// The letters beyond the ASCII ones
+    fn letters(&self) -> &'static str {
+        match self {
+            KeyboardLayout::Us | KeyboardLayout::Uk => "",
+            KeyboardLayout::De => "äöüßÄÖÜ",
+            KeyboardLayout::Fr => "éèçàù",
+            KeyboardLayout::Ro => "ăâîșțĂÂÎȘȚ",
+        }
+    }
+
+    /// Whether a character is typable without AltGr nor dead keys
+    pub fn can_type(&self, c: char) -> bool {
+        c.is_ascii_alphanumeric()
+            || c == ' '
+            || self.symbols().contains(c)
+            || self.letters().contains(c)
+    }
+}
+
+impl fmt::Display for KeyboardLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            KeyboardLayout::Us => "US",
+            KeyboardLayout::Uk => "UK",
+            KeyboardLayout::De => "German (QWERTZ)",
+            KeyboardLayout::Fr => "French (AZERTY)",
+            KeyboardLayout::Ro => "Romanian (standard)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for KeyboardLayout {
+    type Err = ();
+
+    /// Parses the short name of a layout, like `de`
+    fn from_str(layout: &str) -> Result<Self, Self::Err> {
+        match layout.to_ascii_lowercase().as_str() {
+            "us" => Ok(KeyboardLayout::Us),
+            "uk" => Ok(KeyboardLayout::Uk),
+            "de" => Ok(KeyboardLayout::De),
+            "fr" => Ok(KeyboardLayout::Fr),
+            "ro" => Ok(KeyboardLayout::Ro),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Whether a text is typable without AltGr on every layout
+pub fn typable_on_all(layouts: &[KeyboardLayout], text: &str) -> bool {
+    text.chars()
+        .all(|c| layouts.iter().all(|layout| layout.can_type(c)))
+}
+
+/// The settings with their symbol set narrowed to the symbols typable without AltGr on every layout, for people
+/// switching between machines. No layout leaves the settings as they are.
+pub fn restrict_to_layouts(
+    layouts: &[KeyboardLayout],
+    settings: &GenerationSettings,
+) -> GenerationSettings {
+    let mut settings = settings.clone();
+    settings
+        .symbol_set
+        .retain(|&symbol| layouts.iter().all(|layout| layout.can_type(symbol)));
+    settings
+}
+
+/// The symbols of `SYMBOLS` typable without AltGr on every layout
+pub fn common_symbols(layouts: &[KeyboardLayout]) -> Vec<char> {
+    restrict_to_layouts(layouts, &GenerationSettings::default()).symbol_set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_symbols() {
+        assert_eq!(
+            common_symbols(&[KeyboardLayout::Us]),
+            crate::password_bits::SYMBOLS.to_vec()
+        );
+        assert_eq!(
+            common_symbols(&[KeyboardLayout::Fr]),
+            ['!', '$', '%', '&', '*', '(', ')', '-', '_', '+', '=']
+        );
+        assert_eq!(
+            common_symbols(&[KeyboardLayout::De]),
+            ['!', '#', '$', '%', '&', '*', '(', ')', '-', '_', '+', '=']
+        );
+        // Neither @ nor # on a French keyboard, nor ^ anywhere but on US, UK and Romanian ones
+        assert_eq!(
+            common_symbols(&KeyboardLayout::ALL),
+            ['!', '$', '%', '&', '*', '(', ')', '-', '_', '+', '=']
+        );
+        assert_eq!(common_symbols(&[]), crate::password_bits::SYMBOLS.to_vec());
+    }
+
+    #[test]
+    fn test_typability() {
+        assert!(KeyboardLayout::De.can_type('ß'));
+        assert!(!KeyboardLayout::De.can_type('@'));
+        assert!(!KeyboardLayout::De.can_type('^'));
+        assert!(typable_on_all(&KeyboardLayout::ALL, "Cat05!"));
+        assert!(!typable_on_all(
+            &[KeyboardLayout::De, KeyboardLayout::Fr],
+            "Müller"
+        ));
+        assert_eq!("fr".parse(), Ok(KeyboardLayout::Fr));
+        assert_eq!("dvorak".parse::<KeyboardLayout>(), Err(()));
+    }
+}
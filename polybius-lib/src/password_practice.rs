@@ -0,0 +1,185 @@
+//! Practice to remember a password, checking attempts bit by bit and scheduling the reviews
+//!
+//! Reviews follow a spaced repetition schedule: each review recalled right waits longer before the next one, and a
+//! wrong one starts the schedule over.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::password::Password;
+
+/// How long to wait before the next review, in seconds, after each review recalled right in a row
+pub const REVIEW_INTERVALS: [u64; 5] = [60, 10 * 60, 60 * 60, 24 * 60 * 60, 7 * 24 * 60 * 60];
+
+/// Which bits of the password an attempt got right, in order
+///
+/// Bits are matched from both ends of the attempt, so a bit typed wrong or with a character too many doesn't make all
+/// the bits after it wrong.
+pub fn check_attempt(password: &Password, attempt: &str) -> Vec<bool> {
+    let bits: Vec<Zeroizing<Vec<char>>> = password
+        .bits()
+        .iter()
+        .map(|bit| Zeroizing::new(bit.bits.chars().collect()))
+        .collect();
+    let attempt: Zeroizing<Vec<char>> = Zeroizing::new(attempt.chars().collect());
+
+    let mut correct = Vec::with_capacity(bits.len());
+    let mut start = 0;
+    for bit in &bits {
+        if !attempt[start..].starts_with(bit) {
+            break;
+        }
+        start += bit.len();
+        correct.push(true);
+    }
+    let mut end = attempt.len();
+    let mut correct_tail = 0;
+    for bit in bits[correct.len()..].iter().rev() {
+        if !attempt[start..end].ends_with(bit) {
+            break;
+        }
+        end -= bit.len();
+        correct_tail += 1;
+    }
+    if correct.len() + correct_tail == bits.len() && start < end {
+        // Every bit matched but characters are left between them, so the bit before them is wrong
+        if correct.pop().is_none() {
+            correct_tail = correct_tail.saturating_sub(1);
+        }
+    }
+    correct.resize(bits.len() - correct_tail, false);
+    correct.resize(bits.len(), true);
+    correct
+}
+
+/// When a password should be reviewed next, with times in seconds since an epoch of the caller's choice
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PracticeSchedule {
+    /// The number of reviews recalled right in a row
+    pub level: usize,
+    /// When the next review is due
+    pub due: u64,
+}
+
+impl PracticeSchedule {
+    /// A schedule starting with a review due now
+    pub fn new(now: u64) -> Self {
+        Self { level: 0, due: now }
+    }
+
+    pub fn is_due(&self, now: u64) -> bool {
+        now >= self.due
+    }
+
+    /// Whether the password was recalled right through every interval, so it doesn't need practice anymore
+    pub fn is_reliable(&self) -> bool {
+        self.level >= REVIEW_INTERVALS.len()
+    }
+
+    /// Records a review. Attempts made before the review is due don't move the schedule forward, but wrong ones still
+    /// start it over.
+    pub fn record(&mut self, recalled: bool, now: u64) {
+        if !recalled {
+            *self = Self::new(now);
+        } else if self.is_due(now) {
+            let interval = REVIEW_INTERVALS[self.level.min(REVIEW_INTERVALS.len() - 1)];
+            self.level += 1;
+            self.due = now + interval;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::password_bits::PasswordBit;
+
+    use super::*;
+
+    fn password() -> Password {
+        Password::new(
+            [
+                ("Cat", "Cats"),
+                ("05", "Birth Year"),
+                ("!", "Symbol"),
+                ("Le", "Lemons"),
+            ]
+            .iter()
+            .map(|(bits, importance)| PasswordBit::new(bits.to_string(), importance.to_string()))
+            .collect(),
+        )
+    }
+
+    #[test]
+    fn test_check_attempt() {
+        let password = password();
+        assert_eq!(
+            check_attempt(&password, "Cat05!Le"),
+            [true, true, true, true]
+        );
+        assert_eq!(
+            check_attempt(&password, "Cat06!Le"),
+            [true, false, true, true]
+        );
+        // A missing or extra character only makes its own bit wrong
+        assert_eq!(
+            check_attempt(&password, "Ca05!Le"),
+            [false, true, true, true]
+        );
+        assert_eq!(
+            check_attempt(&password, "Cat05!!Le"),
+            [true, true, false, true]
+        );
+        assert_eq!(
+            check_attempt(&password, "Cat05!Lee"),
+            [true, true, true, false]
+        );
+        assert_eq!(check_attempt(&password, ""), [false, false, false, false]);
+        assert_eq!(
+            check_attempt(&password, "Le!05Cat"),
+            [false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn test_schedule() {
+        let mut schedule = PracticeSchedule::new(1000);
+        assert!(schedule.is_due(1000));
+
+        schedule.record(true, 1000);
+        assert_eq!(
+            schedule,
+            PracticeSchedule {
+                level: 1,
+                due: 1060
+            }
+        );
+        assert!(!schedule.is_due(1059));
+
+        // Recalling early doesn't count, forgetting does
+        schedule.record(true, 1030);
+        assert_eq!(schedule.level, 1);
+        schedule.record(false, 1030);
+        assert_eq!(schedule, PracticeSchedule::new(1030));
+
+        let mut now = 1030;
+        for _ in 0..REVIEW_INTERVALS.len() {
+            assert!(!schedule.is_reliable());
+            schedule.record(true, now);
+            now = schedule.due;
+        }
+        assert!(schedule.is_reliable());
+
+        // Reliable passwords are still reviewed, at the longest interval
+        schedule.record(true, now);
+        assert_eq!(
+            schedule.due,
+            now + REVIEW_INTERVALS[REVIEW_INTERVALS.len() - 1]
+        );
+    }
+}
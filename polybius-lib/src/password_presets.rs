@@ -0,0 +1,247 @@
+//! Named site policies, mapping to the settings and the rules to generate a password for them
+//!
+//! Presets are written one per line as `name | bits | passwordrules`, and lines starting with `#` are comments.
+//! The built-in presets are in this format too, so a file can add new presets or replace built-in ones.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::{
+    password_generation::GenerationSettings,
+    password_rules::{PasswordRules, RuleDiagnostic},
+};
+
+/// The presets shipped with Polybius
+pub const BUILTIN_PRESETS: &str = "\
+# name | bits | passwordrules
+wifi-wpa2 | 8 | allowed: ascii-printable; minlength: 8; maxlength: 63
+numeric-pin-4 | 2 | required: digit; minlength: 4; maxlength: 4
+numeric-pin-6 | 3 | required: digit; minlength: 6; maxlength: 6
+legacy-banking | 5 | allowed: upper, lower, digit; minlength: 8; maxlength: 12
+unix-login | 8 | required: lower; required: digit; allowed: upper, special; minlength: 8
+# Windows asks for three of upper, lower, digit and special, which the format can't say, so all but special are required
+windows-domain | 8 | required: upper; required: lower; required: digit; allowed: special; minlength: 8; maxlength: 127
+";
+
+/// A named site policy
+#[derive(Clone, PartialEq, Debug)]
+pub struct PolicyPreset {
+    pub name: String,
+    /// The number of bits to generate, the rules bounding the length in characters
+    pub length: usize,
+    pub rules: PasswordRules,
+}
+
+impl PolicyPreset {
+    /// The settings to generate a password for this policy, along with `rules`
+    pub fn settings(&self) -> GenerationSettings {
+        self.rules
+            .restrict(&GenerationSettings::builder().length(self.length).build())
+    }
+}
+
+/// The reasons a presets file can't be read, along with the line number
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum PresetError {
+    /// The line isn't `name | bits | passwordrules`
+    Malformed(usize),
+    /// The number of bits isn't a positive number
+    InvalidLength(usize),
+    /// The rules have a part that can't be used
+    InvalidRules(usize, RuleDiagnostic),
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PresetError::Malformed(line) => write!(
+                f,
+                "Line {} is not in the name | bits | passwordrules format",
+                line
+            ),
+            PresetError::InvalidLength(line) => {
+                write!(f, "Line {} has an invalid number of bits", line)
+            }
+            PresetError::InvalidRules(line, diagnostic) => {
+                write!(f, "Line {}: {}", line, diagnostic)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PresetError {}
+
+/// A list of presets, looked up by name
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PolicyPresets {
+    presets: Vec<PolicyPreset>,
+}
+
+impl PolicyPresets {
+    /// The presets shipped with Polybius
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN_PRESETS).expect("The built-in presets are valid")
+    }
+
+    /// Parses presets written one per line as `name | bits | passwordrules`
+    pub fn parse(presets: &str) -> Result<Self, PresetError> {
+        let mut parsed = Self::default();
+        for (index, line) in presets.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.splitn(3, '|').map(str::trim);
+            let (Some(name), Some(length), Some(rules)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(PresetError::Malformed(line_number));
+            };
+            if name.is_empty() {
+                return Err(PresetError::Malformed(line_number));
+            }
+            let length = match length.parse::<usize>() {
+                Ok(length) if length > 0 => length,
+                _ => return Err(PresetError::InvalidLength(line_number)),
+            };
+            let (rules, diagnostics) = PasswordRules::parse(rules);
+            if let Some(diagnostic) = diagnostics.into_iter().next() {
+                return Err(PresetError::InvalidRules(line_number, diagnostic));
+            }
+
+            parsed.insert(PolicyPreset {
+                name: name.to_string(),
+                length,
+                rules,
+            });
+        }
+        Ok(parsed)
+    }
+
+    /// Reads presets from a file, see `parse` for the format
+    #[cfg(feature = "std")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        use anyhow::Context;
+
+        let presets = std::fs::read_to_string(path).context("Failed to read the presets file")?;
+        Ok(Self::parse(&presets)?)
+    }
+
+    /// Adds a preset, replacing the one with the same name
+    pub fn insert(&mut self, preset: PolicyPreset) {
+        match self.presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => self.presets.push(preset),
+        }
+    }
+
+    /// Adds every preset of `other`, replacing the ones with the same name
+    pub fn merge(&mut self, other: PolicyPresets) {
+        for preset in other.presets {
+            self.insert(preset);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PolicyPreset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PolicyPreset> {
+        self.presets.iter()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{
+        password_data::{Number, NumberType, PasswordData},
+        password_rules::RuleAwareGeneration,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_builtin() {
+        let presets = PolicyPresets::builtin();
+        let names: Vec<&str> = presets.iter().map(|preset| preset.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "wifi-wpa2",
+                "numeric-pin-4",
+                "numeric-pin-6",
+                "legacy-banking",
+                "unix-login",
+                "windows-domain"
+            ]
+        );
+
+        let wifi = presets.get("wifi-wpa2").unwrap();
+        assert_eq!(wifi.rules.min_length, Some(8));
+        assert_eq!(wifi.rules.max_length, Some(63));
+        assert_eq!(wifi.settings().length, 8);
+        assert!(presets
+            .get("legacy-banking")
+            .unwrap()
+            .settings()
+            .symbol_set
+            .is_empty());
+    }
+
+    #[test]
+    fn test_generate_with_preset() {
+        let password_data = PasswordData::new(
+            [
+                Number::new(2005, NumberType::BirthYear),
+                Number::new(12, NumberType::BirthMonth),
+            ]
+            .to_vec(),
+            ["Cats", "Lemons"].map(String::from).to_vec(),
+        );
+        let presets = PolicyPresets::builtin();
+        for name in ["numeric-pin-4", "legacy-banking", "windows-domain"] {
+            let preset = presets.get(name).unwrap();
+            let password = password_data
+                .generate_password_with_rules(&preset.settings(), &preset.rules, 10_000)
+                .unwrap();
+            assert_eq!(preset.rules.check(&password), Ok(()), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let mut presets = PolicyPresets::builtin();
+        presets.merge(
+            PolicyPresets::parse(
+                "# Our systems\nmainframe | 4 | allowed: upper, digit; maxlength: 8\n\nwifi-wpa2 | 10 | minlength: 12\n",
+            )
+            .unwrap(),
+        );
+        assert_eq!(presets.get("mainframe").unwrap().rules.max_length, Some(8));
+        assert_eq!(presets.get("wifi-wpa2").unwrap().length, 10);
+        assert_eq!(presets.iter().count(), 7);
+
+        assert_eq!(
+            PolicyPresets::parse("broken"),
+            Err(PresetError::Malformed(1))
+        );
+        assert_eq!(
+            PolicyPresets::parse("# comment\npin | zero | required: digit"),
+            Err(PresetError::InvalidLength(2))
+        );
+        assert_eq!(
+            PolicyPresets::parse("pin | 2 | required: emoji"),
+            Err(PresetError::InvalidRules(
+                1,
+                RuleDiagnostic::UnknownClass("emoji".to_string())
+            ))
+        );
+    }
+}
@@ -0,0 +1,212 @@
+//! A file holding the data and settings of a person, to move them between browsers and devices
+//!
+//! Profiles are JSON with a `version` field checked before anything else is read, then either the `profile` itself
+//! or the profile `sealed` by a `Vault`:
+//!
+//! ```json
+//! {"version": 1, "profile": {"data": {...}, "settings": {...}, "add_current_year": false, "keyboard_layouts": []}}
+//! {"version": 1, "sealed": "1.600000.<salt>.<nonce>.<ciphertext>"}
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::{
+    password_data::PasswordData,
+    password_generation::GenerationSettings,
+    password_layouts::KeyboardLayout,
+    password_vault::{Vault, VaultError},
+};
+
+/// The version of the profile format written by this library
+pub const PROFILE_VERSION: u32 = 1;
+
+/// The reasons a profile can't be imported
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum ProfileError {
+    /// The file is not a profile
+    Malformed,
+    /// The profile is of a version this library can't read, like one written by a newer Polybius
+    UnsupportedVersion(u32),
+    /// The profile is encrypted and no passphrase was given
+    PassphraseNeeded,
+    /// The encrypted profile can't be opened
+    Vault(VaultError),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProfileError::Malformed => write!(f, "The file is not a Polybius profile"),
+            ProfileError::UnsupportedVersion(version) => write!(
+                f,
+                "The profile version {} is not supported, only version {} is",
+                version, PROFILE_VERSION
+            ),
+            ProfileError::PassphraseNeeded => write!(f, "The profile is encrypted"),
+            ProfileError::Vault(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl From<VaultError> for ProfileError {
+    fn from(error: VaultError) -> Self {
+        ProfileError::Vault(error)
+    }
+}
+
+/// The data and settings passwords are generated from
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Profile {
+    pub data: PasswordData,
+    pub settings: GenerationSettings,
+    /// Whether the current year is added to the numbers when generating
+    #[serde(default)]
+    pub add_current_year: bool,
+    /// The keyboard layouts the symbols must be typable on, see `restrict_to_layouts`
+    #[serde(default)]
+    pub keyboard_layouts: Vec<KeyboardLayout>,
+}
+
+/// Only the version, to check it before reading a format that may have changed
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Contents {
+    Profile(Profile),
+    Sealed(String),
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProfileFile {
+    version: u32,
+    #[serde(flatten)]
+    contents: Contents,
+}
+
+impl Profile {
+    pub fn new(data: PasswordData, settings: GenerationSettings) -> Self {
+        Self {
+            data,
+            settings,
+            add_current_year: false,
+            keyboard_layouts: Vec::new(),
+        }
+    }
+
+    /// Writes the profile file, encrypted when there is a vault
+    pub fn export(&self, vault: Option<&Vault>) -> String {
+        let contents = match vault {
+            Some(vault) => Contents::Sealed(vault.seal(&self.to_json())),
+            None => Contents::Profile(self.clone()),
+        };
+        let file = ProfileFile {
+            version: PROFILE_VERSION,
+            contents,
+        };
+        serde_json::to_string_pretty(&file).expect("A profile is always serializable")
+    }
+
+    /// Reads a profile file, opening it with the passphrase when it is encrypted
+    pub fn import(file: &str, passphrase: Option<&str>) -> Result<Self, ProfileError> {
+        let header: Header = serde_json::from_str(file).map_err(|_| ProfileError::Malformed)?;
+        if header.version != PROFILE_VERSION {
+            return Err(ProfileError::UnsupportedVersion(header.version));
+        }
+
+        let file: ProfileFile = serde_json::from_str(file).map_err(|_| ProfileError::Malformed)?;
+        match file.contents {
+            Contents::Profile(profile) => Ok(profile),
+            Contents::Sealed(sealed) => {
+                let passphrase = passphrase.ok_or(ProfileError::PassphraseNeeded)?;
+                let json = Vault::unlock(passphrase, &sealed)?.open(&sealed)?;
+                serde_json::from_slice(&json).map_err(|_| ProfileError::Malformed)
+            }
+        }
+    }
+
+    fn to_json(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(serde_json::to_vec(self).expect("A profile is always serializable"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::password_data::{Number, NumberType};
+
+    use super::*;
+
+    fn profile() -> Profile {
+        let mut profile = Profile::new(
+            PasswordData::new(
+                [Number::new(2005, NumberType::BirthYear)].to_vec(),
+                ["Cats", "Lemons"].map(String::from).to_vec(),
+            ),
+            GenerationSettings::builder().length(6).build(),
+        );
+        profile.keyboard_layouts = vec![KeyboardLayout::Fr];
+        profile
+    }
+
+    #[test]
+    fn test_plain_profile() {
+        let file = profile().export(None);
+        assert!(file.contains("\"version\": 1"));
+        assert!(file.contains("Lemons"));
+        assert_eq!(Profile::import(&file, None), Ok(profile()));
+        // The passphrase is only needed for encrypted profiles
+        assert_eq!(Profile::import(&file, Some("correct horse")), Ok(profile()));
+    }
+
+    #[test]
+    fn test_encrypted_profile() {
+        let vault = Vault::with_rounds("correct horse", 1000);
+        let file = profile().export(Some(&vault));
+        assert!(!file.contains("Lemons"));
+        assert_eq!(Profile::import(&file, Some("correct horse")), Ok(profile()));
+        assert_eq!(
+            Profile::import(&file, None),
+            Err(ProfileError::PassphraseNeeded)
+        );
+        assert_eq!(
+            Profile::import(&file, Some("battery staple")),
+            Err(ProfileError::Vault(VaultError::WrongPassphrase))
+        );
+    }
+
+    #[test]
+    fn test_versions() {
+        // Any other version is refused before its contents are read
+        assert_eq!(
+            Profile::import(r#"{"version": 2, "profile": "changed"}"#, None),
+            Err(ProfileError::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            Profile::import(r#"{"version": 0, "profile": {}}"#, None),
+            Err(ProfileError::UnsupportedVersion(0))
+        );
+        assert_eq!(
+            Profile::import(r#"{"profile": {}}"#, None),
+            Err(ProfileError::Malformed)
+        );
+        assert_eq!(Profile::import("Cats", None), Err(ProfileError::Malformed));
+
+        // Fields added later are optional
+        let file = r#"{"version": 1, "profile": {
+            "data": {"numbers_poll": [], "text_poll": ["Cats"]},
+            "settings": {"length": 4, "symbols": false, "symbol_set": []}
+        }}"#;
+        let profile = Profile::import(file, None).unwrap();
+        assert_eq!(profile.data.text_poll, ["Cats"]);
+        assert!(profile.keyboard_layouts.is_empty());
+    }
+}
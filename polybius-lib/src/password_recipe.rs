@@ -0,0 +1,424 @@
+//! Recipes rebuild a password from the data instead of storing it
+//!
+//! A recipe lists where each bit of a password comes from, so it can be stored next to an account and replayed
+//! against the same `PasswordData` and `GenerationSettings` to get the password back. Its compact form reads
+//! `2/n0t.t1:3.s4~5e0c9f3a`: the version, then a number written truncated, the first three characters of a text and
+//! a symbol of the symbol set, then a check of the data entries and symbols used.
+//!
+//! A recipe names no entry, but it is not a secret to publish: whoever has it can test guesses of the entries
+//! against its check offline, and entries like birth dates have few possible values.
+
+use alloc::{string::ToString, vec::Vec};
+use core::{fmt, str::FromStr};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    password::Password,
+    password_bits::{BitSource, PasswordBit},
+    password_data::PasswordData,
+    password_generation::GenerationSettings,
+};
+
+/// The version of the compact form
+const RECIPE_VERSION: u32 = 2;
+
+/// How to rebuild a password from a `PasswordData`
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Recipe {
+    /// Where each bit comes from, in order. Symbol indexes are into the symbol set of the settings.
+    pub steps: Vec<BitSource>,
+    /// A check of the data entries and symbols the steps use, to notice when they changed. It confirms a guessed entry
+    /// as well, so a recipe must be kept as private as the data.
+    pub check: u32,
+}
+
+/// The reasons a recipe can't be made or replayed
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum RecipeError {
+    /// The bit at this position can't be made from the data
+    UnknownBit(usize),
+    /// The step at this index uses an entry the data doesn't have anymore
+    MissingEntry(usize),
+    /// The step at this index uses a form its entry can't be written in anymore, like a truncated relevant number or
+    /// a symbol out of the symbol set
+    FormUnavailable(usize),
+    /// The entries the recipe uses are not the ones it was made from
+    ProfileChanged,
+    /// The compact form can't be read
+    Malformed,
+    /// The compact form is of another version, like the 16-bit checks of version 1
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecipeError::UnknownBit(position) => write!(
+                f,
+                "The bit number {} can't be made from the data",
+                position + 1
+            ),
+            RecipeError::MissingEntry(step) => write!(
+                f,
+                "Step {} uses an entry that was removed from the data",
+                step + 1
+            ),
+            RecipeError::FormUnavailable(step) => write!(
+                f,
+                "Step {} uses an entry that can't be written the same way anymore",
+                step + 1
+            ),
+            RecipeError::ProfileChanged => write!(
+                f,
+                "The data changed since the recipe was made, it can't rebuild the password"
+            ),
+            RecipeError::Malformed => write!(f, "The recipe can't be read"),
+            RecipeError::UnsupportedVersion(version) => {
+                write!(f, "The recipe version {} is not supported", version)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RecipeError {}
+
+impl Recipe {
+    /// Records where each bit of a password comes from in the data, with the settings it was generated with
+    pub fn record(
+        data: &PasswordData,
+        settings: &GenerationSettings,
+        password: &Password,
+    ) -> Result<Self, RecipeError> {
+        let emissions = data.emissions(settings);
+        let steps = password
+            .bits()
+            .iter()
+            .enumerate()
+            .map(|(position, bit)| {
+                emissions
+                    .iter()
+                    .find(|e| e.bit == *bit)
+                    .map(|e| e.source)
+                    .ok_or(RecipeError::UnknownBit(position))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let check = check(data, settings, &steps)?;
+        Ok(Self { steps, check })
+    }
+
+    /// Rebuilds the password from the data, with the settings it was recorded with
+    pub fn replay(
+        &self,
+        data: &PasswordData,
+        settings: &GenerationSettings,
+    ) -> Result<Password, RecipeError> {
+        if check(data, settings, &self.steps)? != self.check {
+            return Err(RecipeError::ProfileChanged);
+        }
+
+        let mut bits = Vec::with_capacity(self.steps.len());
+        for (step, source) in self.steps.iter().enumerate() {
+            let bit = match *source {
+                BitSource::Number { index, truncated } => {
+                    let number = &data.numbers_poll[index];
+                    let (_, form, _) = PasswordBit::number_forms(number)
+                        .into_iter()
+                        .find(|(t, _, _)| *t == truncated)
+                        .ok_or(RecipeError::FormUnavailable(step))?;
+                    PasswordBit::new(form, number.num_type.to_string())
+                }
+                BitSource::Text { index, length } => {
+                    let text = &data.text_poll[index];
+                    if text.chars().count() < length {
+                        return Err(RecipeError::FormUnavailable(step));
+                    }
+                    PasswordBit::new(text.chars().take(length).collect(), text.to_string())
+                }
+                BitSource::Symbol { index } => {
+                    let symbol = settings
+                        .symbol_set
+                        .get(index)
+                        .ok_or(RecipeError::FormUnavailable(step))?;
+                    PasswordBit::new(symbol.to_string(), "Symbol".to_string())
+                }
+            };
+            bits.push(bit);
+        }
+        Ok(Password::new(bits))
+    }
+}
+
+/// Hashes the entries and symbols the steps use with FNV-1a
+fn check(
+    data: &PasswordData,
+    settings: &GenerationSettings,
+    steps: &[BitSource],
+) -> Result<u32, RecipeError> {
+    let mut hash: u32 = 0x811c_9dc5;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= u32::from(*byte);
+            hash = hash.wrapping_mul(0x0100_0193);
+        }
+    };
+
+    for (step, source) in steps.iter().enumerate() {
+        match *source {
+            BitSource::Number { index, .. } => {
+                let number = data
+                    .numbers_poll
+                    .get(index)
+                    .ok_or(RecipeError::MissingEntry(step))?;
+                feed(b"n");
+                feed(&number.value.to_le_bytes());
+                feed(number.num_type.to_string().as_bytes());
+            }
+            BitSource::Text { index, .. } => {
+                let text = data
+                    .text_poll
+                    .get(index)
+                    .ok_or(RecipeError::MissingEntry(step))?;
+                feed(b"t");
+                feed(text.as_bytes());
+            }
+            BitSource::Symbol { index } => {
+                // A reordered or narrowed symbol set would replay another symbol at the same index
+                let symbol = settings
+                    .symbol_set
+                    .get(index)
+                    .ok_or(RecipeError::FormUnavailable(step))?;
+                feed(b"s");
+                feed(symbol.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+        }
+        feed(b";");
+    }
+    Ok(hash)
+}
+
+impl fmt::Display for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/", RECIPE_VERSION)?;
+        for (step, source) in self.steps.iter().enumerate() {
+            if step > 0 {
+                write!(f, ".")?;
+            }
+            match source {
+                BitSource::Number { index, truncated } => {
+                    write!(f, "n{}{}", index, if *truncated { "t" } else { "" })?
+                }
+                BitSource::Text { index, length } => write!(f, "t{}:{}", index, length)?,
+                BitSource::Symbol { index } => write!(f, "s{}", index)?,
+            }
+        }
+        write!(f, "~{:08x}", self.check)
+    }
+}
+
+impl FromStr for Recipe {
+    type Err = RecipeError;
+
+    fn from_str(recipe: &str) -> Result<Self, Self::Err> {
+        let (version, rest) = recipe.split_once('/').ok_or(RecipeError::Malformed)?;
+        let version: u32 = version.parse().map_err(|_| RecipeError::Malformed)?;
+        if version != RECIPE_VERSION {
+            return Err(RecipeError::UnsupportedVersion(version));
+        }
+        let (steps, check) = rest.rsplit_once('~').ok_or(RecipeError::Malformed)?;
+        let check = u32::from_str_radix(check, 16).map_err(|_| RecipeError::Malformed)?;
+
+        let number = |value: &str| value.parse::<usize>().map_err(|_| RecipeError::Malformed);
+        let mut parsed = Vec::new();
+        for step in steps.split('.').filter(|step| !step.is_empty()) {
+            if !step.is_char_boundary(1) {
+                return Err(RecipeError::Malformed);
+            }
+            let source = match step.split_at(1) {
+                ("n", index) => match index.strip_suffix('t') {
+                    Some(index) => BitSource::Number {
+                        index: number(index)?,
+                        truncated: true,
+                    },
+                    None => BitSource::Number {
+                        index: number(index)?,
+                        truncated: false,
+                    },
+                },
+                ("t", rest) => {
+                    let (index, length) = rest.split_once(':').ok_or(RecipeError::Malformed)?;
+                    BitSource::Text {
+                        index: number(index)?,
+                        length: number(length)?,
+                    }
+                }
+                ("s", index) => BitSource::Symbol {
+                    index: number(index)?,
+                },
+                _ => return Err(RecipeError::Malformed),
+            };
+            parsed.push(source);
+        }
+        Ok(Self {
+            steps: parsed,
+            check,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{
+        password_data::{Number, NumberType},
+        password_generation::PasswordGeneration,
+    };
+
+    use super::*;
+
+    fn data() -> PasswordData {
+        PasswordData::new(
+            [
+                Number::new(2005, NumberType::BirthYear),
+                Number::new(12, NumberType::BirthMonth),
+            ]
+            .to_vec(),
+            ["Cats", "Lemons", "Apples"].map(String::from).to_vec(),
+        )
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let data = data();
+        let settings = GenerationSettings::builder().length(10).build();
+        for _ in 0..20 {
+            let password = data.generate_password(&settings);
+            let recipe = Recipe::record(&data, &settings, &password).unwrap();
+            assert_eq!(recipe.steps.len(), password.bit_count());
+            assert_eq!(recipe.replay(&data, &settings).unwrap(), password);
+
+            let compact = recipe.to_string();
+            assert!(!compact.contains("Cat"));
+            assert_eq!(compact.parse::<Recipe>().unwrap(), recipe);
+        }
+    }
+
+    #[test]
+    fn test_compact_form() {
+        let recipe = Recipe {
+            steps: vec![
+                BitSource::Number {
+                    index: 0,
+                    truncated: true,
+                },
+                BitSource::Text {
+                    index: 1,
+                    length: 3,
+                },
+                BitSource::Symbol { index: 4 },
+                BitSource::Number {
+                    index: 12,
+                    truncated: false,
+                },
+            ],
+            check: 0x5e0c_9f3a,
+        };
+        assert_eq!(recipe.to_string(), "2/n0t.t1:3.s4.n12~5e0c9f3a");
+        assert_eq!("2/n0t.t1:3.s4.n12~5e0c9f3a".parse::<Recipe>(), Ok(recipe));
+        assert_eq!(
+            "1/n0~0000".parse::<Recipe>(),
+            Err(RecipeError::UnsupportedVersion(1))
+        );
+        assert_eq!("2/x0~0000".parse::<Recipe>(), Err(RecipeError::Malformed));
+        assert_eq!("2/n0".parse::<Recipe>(), Err(RecipeError::Malformed));
+        assert_eq!("2/ü0~0000".parse::<Recipe>(), Err(RecipeError::Malformed));
+    }
+
+    #[test]
+    fn test_symbol_set() {
+        let data = data();
+        let settings = GenerationSettings::builder().symbol_set(['%', '&']).build();
+        let password = Password::new(vec![PasswordBit::new(
+            "&".to_string(),
+            "Symbol".to_string(),
+        )]);
+        let recipe = Recipe::record(&data, &settings, &password).unwrap();
+        assert_eq!(recipe.steps, vec![BitSource::Symbol { index: 1 }]);
+        assert_eq!(recipe.replay(&data, &settings), Ok(password));
+
+        let other = GenerationSettings::builder().symbol_set(['%']).build();
+        assert_eq!(
+            recipe.replay(&data, &other),
+            Err(RecipeError::FormUnavailable(0))
+        );
+
+        // The same index in another symbol set is another symbol
+        let reordered = GenerationSettings::builder().symbol_set(['&', '%']).build();
+        assert_eq!(
+            recipe.replay(&data, &reordered),
+            Err(RecipeError::ProfileChanged)
+        );
+        let changed = GenerationSettings::builder().symbol_set(['%', '#']).build();
+        assert_eq!(
+            recipe.replay(&data, &changed),
+            Err(RecipeError::ProfileChanged)
+        );
+    }
+
+    #[test]
+    fn test_profile_changed() {
+        let data = data();
+        let settings = GenerationSettings::default();
+        let recipe: Recipe = Recipe::record(
+            &data,
+            &settings,
+            &Password::new(vec![
+                PasswordBit::new("05".to_string(), "Birth Year".to_string()),
+                PasswordBit::new("Lem".to_string(), "Lemons".to_string()),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(recipe.to_string().split('~').next(), Some("2/n0t.t1:3"));
+
+        // Entries the recipe doesn't use can change
+        let mut changed = data.clone();
+        changed.text_poll[2] = "Pears".to_string();
+        changed
+            .numbers_poll
+            .push(Number::new(7, NumberType::RelevantNumber));
+        assert!(recipe.replay(&changed, &settings).is_ok());
+
+        let mut changed = data.clone();
+        changed.text_poll[1] = "Limes".to_string();
+        assert_eq!(
+            recipe.replay(&changed, &settings),
+            Err(RecipeError::ProfileChanged)
+        );
+
+        let mut changed = data.clone();
+        changed.numbers_poll[0].num_type = NumberType::RelevantNumber;
+        assert_eq!(
+            recipe.replay(&changed, &settings),
+            Err(RecipeError::ProfileChanged)
+        );
+
+        let mut changed = data.clone();
+        changed.text_poll.truncate(1);
+        assert_eq!(
+            recipe.replay(&changed, &settings),
+            Err(RecipeError::MissingEntry(1))
+        );
+
+        let unknown = Password::new(vec![PasswordBit::new(
+            "Dog".to_string(),
+            "Dogs".to_string(),
+        )]);
+        assert_eq!(
+            Recipe::record(&data, &settings, &unknown),
+            Err(RecipeError::UnknownBit(0))
+        );
+    }
+}
@@ -0,0 +1,526 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashSet},
+    ops::RangeInclusive,
+};
+
+use anyhow::{bail, Context};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    password_bits::{BitCategory, BitSource},
+    password_data::{NumberType, PasswordData},
+    password_generation::{Emission, GenerationSettings},
+    password_segmentation::Segmentation,
+};
+
+/// What is remembered about a single bit of a forgotten password
+#[derive(Clone, PartialEq, Debug)]
+pub enum TemplateBit {
+    /// Nothing is remembered about this bit
+    Any,
+    /// A bit of the given kind
+    Category(BitCategory),
+    /// A number of the given type, like a birth year
+    Number(NumberType),
+    /// A bit made from this exact text of the text poll
+    Text(String),
+}
+
+/// Narrows down the candidates of a forgotten password
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RecoveryConstraints {
+    /// The shape of the password, one entry per bit. Without it every password of `GenerationSettings::length` bits is a candidate
+    pub template: Option<Vec<TemplateBit>>,
+    /// The range of the password length in characters
+    pub length: Option<RangeInclusive<usize>>,
+    /// Parts of the password that are remembered exactly
+    pub fragments: Vec<String>,
+}
+
+/// A password that the generator could have produced, with the bits it is made of
+#[derive(Clone, PartialEq, Debug)]
+pub struct Candidate {
+    /// The password itself
+    pub password: String,
+    /// The most likely bits the password was generated from
+    pub segmentation: Segmentation,
+}
+
+/// Checks candidates against something that identifies the forgotten password, usually a hash of it
+pub trait PasswordVerifier {
+    /// Returns true if the candidate is the forgotten password
+    fn verify(&self, candidate: &str) -> bool;
+}
+
+impl<F: Fn(&str) -> bool> PasswordVerifier for F {
+    fn verify(&self, candidate: &str) -> bool {
+        self(candidate)
+    }
+}
+
+/// Verifies candidates against a hex encoded SHA-256 digest of the password
+pub struct Sha256Verifier {
+    digest: Vec<u8>,
+}
+
+impl Sha256Verifier {
+    /// Creates a new Sha256Verifier from a hex encoded digest
+    pub fn from_hex(digest: &str) -> anyhow::Result<Self> {
+        let digest = hex::decode(digest.trim()).context("The SHA-256 digest is not valid hex")?;
+        if digest.len() != 32 {
+            bail!("A SHA-256 digest is 32 bytes long, got {}", digest.len());
+        }
+        Ok(Self { digest })
+    }
+}
+
+impl PasswordVerifier for Sha256Verifier {
+    fn verify(&self, candidate: &str) -> bool {
+        Sha256::digest(candidate.as_bytes()).as_slice() == self.digest
+    }
+}
+
+/// Verifies candidates against a PBKDF2-HMAC-SHA256 derived key
+pub struct Pbkdf2Verifier {
+    salt: Vec<u8>,
+    rounds: u32,
+    key: Vec<u8>,
+}
+
+impl Pbkdf2Verifier {
+    /// Creates a new Pbkdf2Verifier from the salt, the number of rounds and the hex encoded derived key
+    pub fn from_hex(salt: &[u8], rounds: u32, key: &str) -> anyhow::Result<Self> {
+        let key = hex::decode(key.trim()).context("The PBKDF2 key is not valid hex")?;
+        if key.is_empty() {
+            bail!("The PBKDF2 key is empty");
+        }
+        Ok(Self {
+            salt: salt.to_vec(),
+            rounds,
+            key,
+        })
+    }
+}
+
+impl PasswordVerifier for Pbkdf2Verifier {
+    fn verify(&self, candidate: &str) -> bool {
+        let mut key = vec![0u8; self.key.len()];
+        pbkdf2::pbkdf2_hmac::<Sha256>(candidate.as_bytes(), &self.salt, self.rounds, &mut key);
+        key == self.key
+    }
+}
+
+/// Verifies candidates against a bcrypt hash, like `$2b$12$...`
+#[cfg(feature = "bcrypt")]
+pub struct BcryptVerifier {
+    hash: String,
+}
+
+#[cfg(feature = "bcrypt")]
+impl BcryptVerifier {
+    /// Creates a new BcryptVerifier from a bcrypt hash string
+    pub fn new(hash: &str) -> anyhow::Result<Self> {
+        // Parse the hash once so a malformed one fails here instead of on every candidate
+        bcrypt::verify("", hash).context("The bcrypt hash is not valid")?;
+        Ok(Self {
+            hash: hash.to_string(),
+        })
+    }
+}
+
+#[cfg(feature = "bcrypt")]
+impl PasswordVerifier for BcryptVerifier {
+    fn verify(&self, candidate: &str) -> bool {
+        bcrypt::verify(candidate, &self.hash).unwrap_or(false)
+    }
+}
+
+pub trait PasswordRecovery {
+    /// Lists the passwords the generator could have produced from this data, by the probability of their most likely
+    /// bits. Only the candidates matching the constraints are listed, and each password is listed once
+    fn password_candidates(
+        &self,
+        settings: &GenerationSettings,
+        constraints: &RecoveryConstraints,
+    ) -> Candidates;
+
+    /// Goes through at most `max_attempts` candidates and returns the first one accepted by the verifier
+    fn recover_password(
+        &self,
+        settings: &GenerationSettings,
+        constraints: &RecoveryConstraints,
+        verifier: &impl PasswordVerifier,
+        max_attempts: usize,
+    ) -> Option<Candidate> {
+        self.password_candidates(settings, constraints)
+            .take(max_attempts)
+            .find(|candidate| verifier.verify(&candidate.password))
+    }
+}
+
+impl PasswordRecovery for PasswordData {
+    fn password_candidates(
+        &self,
+        settings: &GenerationSettings,
+        constraints: &RecoveryConstraints,
+    ) -> Candidates {
+        // Empty bits would list the same password many times
+        let emissions: Vec<Emission> = self
+            .emissions(settings)
+            .into_iter()
+            .filter(|e| !e.bit.bits.is_empty())
+            .collect();
+
+        let template = match &constraints.template {
+            Some(template) => template.clone(),
+            None => vec![TemplateBit::Any; settings.length],
+        };
+
+        // For every position, the emissions allowed there, the most likely first
+        let positions: Vec<Vec<usize>> = template
+            .iter()
+            .map(|template_bit| {
+                let mut allowed: Vec<usize> = (0..emissions.len())
+                    .filter(|&i| self.template_allows(template_bit, &emissions[i].source))
+                    .collect();
+                allowed.sort_by(|&a, &b| {
+                    emissions[b]
+                        .probability
+                        .total_cmp(&emissions[a].probability)
+                });
+                allowed
+            })
+            .collect();
+
+        Candidates::new(
+            emissions,
+            positions,
+            constraints.length.clone().unwrap_or(0..=usize::MAX),
+            constraints.fragments.clone(),
+        )
+    }
+}
+
+impl PasswordData {
+    fn template_allows(&self, template_bit: &TemplateBit, source: &BitSource) -> bool {
+        match (template_bit, source) {
+            (TemplateBit::Any, _) => true,
+            (TemplateBit::Category(category), source) => source.category() == *category,
+            (TemplateBit::Number(num_type), BitSource::Number { index, .. }) => {
+                self.numbers_poll[*index].num_type == *num_type
+            }
+            (TemplateBit::Text(text), BitSource::Text { index, .. }) => {
+                self.text_poll[*index] == *text
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A partial password, as the index of the chosen emission at each position so far
+struct Node {
+    probability: f64,
+    choices: Vec<usize>,
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Node {}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.probability.total_cmp(&other.probability)
+    }
+}
+
+/// An iterator over password candidates, in descending order of the probability of their most likely bits
+///
+/// It is a best-first search where every partial password has a next sibling, the same password with the next most likely bit
+/// on its last position, and a first child, the password extended with the most likely bit on the next position. Both are at
+/// most as likely as their parent, so popping the most likely partial password always reaches the complete ones in order.
+///
+/// A password that several bit sequences make is listed at its most likely one, so a password can come after a less likely
+/// one when its other sequences are added up, as `password_probability` does. Every listed password is remembered to list
+/// it once, so the memory grows with the number of candidates gone through, around a hundred bytes each.
+pub struct Candidates {
+    emissions: Vec<Emission>,
+    positions: Vec<Vec<usize>>,
+    /// The longest bit allowed at each position, in characters
+    longest: Vec<usize>,
+    length: RangeInclusive<usize>,
+    fragments: Vec<String>,
+    heap: BinaryHeap<Node>,
+    /// The passwords listed so far
+    seen: HashSet<String>,
+}
+
+impl Candidates {
+    fn new(
+        emissions: Vec<Emission>,
+        positions: Vec<Vec<usize>>,
+        length: RangeInclusive<usize>,
+        fragments: Vec<String>,
+    ) -> Self {
+        let longest = positions
+            .iter()
+            .map(|allowed| {
+                allowed
+                    .iter()
+                    .map(|&i| emissions[i].bit.bits.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut candidates = Self {
+            emissions,
+            positions,
+            longest,
+            length,
+            fragments,
+            heap: BinaryHeap::new(),
+            seen: HashSet::new(),
+        };
+        if candidates.positions.first().is_some_and(|p| !p.is_empty()) {
+            candidates.push(vec![0]);
+        }
+        candidates
+    }
+
+    fn emission(&self, position: usize, choice: usize) -> &Emission {
+        &self.emissions[self.positions[position][choice]]
+    }
+
+    fn push(&mut self, choices: Vec<usize>) {
+        let probability = choices
+            .iter()
+            .enumerate()
+            .map(|(position, &choice)| self.emission(position, choice).probability)
+            .product();
+        self.heap.push(Node {
+            probability,
+            choices,
+        });
+    }
+
+    fn password(&self, choices: &[usize]) -> String {
+        choices
+            .iter()
+            .enumerate()
+            .map(|(position, &choice)| self.emission(position, choice).bit.bits.as_str())
+            .collect()
+    }
+}
+
+impl Iterator for Candidates {
+    type Item = Candidate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.heap.pop() {
+            let depth = node.choices.len();
+            let last = depth - 1;
+
+            if node.choices[last] + 1 < self.positions[last].len() {
+                let mut sibling = node.choices.clone();
+                sibling[last] += 1;
+                self.push(sibling);
+            }
+
+            let password = self.password(&node.choices);
+            let characters = password.chars().count();
+            if characters > *self.length.end() {
+                continue;
+            }
+
+            if depth < self.positions.len() {
+                let reachable = characters + self.longest[depth..].iter().sum::<usize>();
+                if !self.positions[depth].is_empty() && reachable >= *self.length.start() {
+                    let mut child = node.choices;
+                    child.push(0);
+                    self.push(child);
+                }
+                continue;
+            }
+
+            if characters < *self.length.start()
+                || !self.fragments.iter().all(|f| password.contains(f.as_str()))
+                || !self.seen.insert(password.clone())
+            {
+                continue;
+            }
+
+            let emissions: Vec<&Emission> = node
+                .choices
+                .iter()
+                .enumerate()
+                .map(|(position, &choice)| self.emission(position, choice))
+                .collect();
+            return Some(Candidate {
+                password,
+                segmentation: Segmentation {
+                    bits: emissions.iter().map(|e| e.bit.clone()).collect(),
+                    sources: emissions.iter().map(|e| e.source).collect(),
+                    probability: node.probability,
+                },
+            });
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::password_data::Number;
+
+    use super::*;
+
+    fn example_data() -> PasswordData {
+        PasswordData::new(
+            [
+                Number::new(2005, NumberType::BirthYear),
+                Number::new(12, NumberType::BirthMonth),
+            ]
+            .to_vec(),
+            ["Cats", "Lemons", "Oranges"].map(String::from).to_vec(),
+        )
+    }
+
+    fn settings() -> GenerationSettings {
+        GenerationSettings::builder()
+            .length(3)
+            .symbols(true)
+            .build()
+    }
+
+    #[test]
+    fn test_candidates_descending() {
+        let candidates: Vec<Candidate> = example_data()
+            .password_candidates(&settings(), &RecoveryConstraints::default())
+            .take(500)
+            .collect();
+        assert_eq!(candidates.len(), 500);
+        for pair in candidates.windows(2) {
+            assert!(pair[0].segmentation.probability >= pair[1].segmentation.probability);
+        }
+        let unique: HashSet<&String> = candidates.iter().map(|c| &c.password).collect();
+        assert_eq!(unique.len(), candidates.len());
+    }
+
+    #[test]
+    fn test_candidates_template() {
+        let constraints = RecoveryConstraints {
+            template: Some(vec![
+                TemplateBit::Text("Cats".to_string()),
+                TemplateBit::Number(NumberType::BirthYear),
+                TemplateBit::Category(BitCategory::Symbol),
+            ]),
+            ..Default::default()
+        };
+        let candidates: Vec<Candidate> = example_data()
+            .password_candidates(&settings(), &constraints)
+            .collect();
+        // 3 prefixes of Cats, 2 ways to write 2005 and 14 symbols
+        assert_eq!(candidates.len(), 3 * 2 * 14);
+        assert!(candidates.iter().all(|c| c.password.starts_with('C')));
+        assert!(candidates.iter().any(|c| c.password == "Cat05!"));
+    }
+
+    #[test]
+    fn test_candidates_length_and_fragments() {
+        let constraints = RecoveryConstraints {
+            length: Some(8..=9),
+            fragments: vec!["Lem".to_string()],
+            ..Default::default()
+        };
+        let candidates: Vec<Candidate> = example_data()
+            .password_candidates(&settings(), &constraints)
+            .collect();
+        assert!(!candidates.is_empty());
+        for candidate in candidates {
+            let length = candidate.password.chars().count();
+            assert!((8..=9).contains(&length), "{}", candidate.password);
+            assert!(candidate.password.contains("Lem"));
+        }
+    }
+
+    #[test]
+    fn test_recover_password_sha256() {
+        let digest = hex::encode(Sha256::digest(b"Ora2005Le"));
+        let verifier = Sha256Verifier::from_hex(&digest).unwrap();
+        let candidate = example_data()
+            .recover_password(
+                &settings(),
+                &RecoveryConstraints::default(),
+                &verifier,
+                usize::MAX,
+            )
+            .expect("The password should be recovered");
+        assert_eq!(candidate.password, "Ora2005Le");
+        assert_eq!(candidate.segmentation.bits[1].importance, "Birth Year");
+    }
+
+    #[test]
+    fn test_recover_password_pbkdf2() {
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(b"12Cat", b"salt", 1000, &mut key);
+        let verifier = Pbkdf2Verifier::from_hex(b"salt", 1000, &hex::encode(key)).unwrap();
+        let constraints = RecoveryConstraints {
+            template: Some(vec![
+                TemplateBit::Category(BitCategory::Number),
+                TemplateBit::Any,
+            ]),
+            ..Default::default()
+        };
+        let candidate = example_data().recover_password(&settings(), &constraints, &verifier, 1000);
+        assert_eq!(candidate.map(|c| c.password), Some("12Cat".to_string()));
+    }
+
+    #[test]
+    fn test_recover_password_not_found() {
+        let constraints = RecoveryConstraints {
+            template: Some(vec![TemplateBit::Category(BitCategory::Symbol)]),
+            ..Default::default()
+        };
+        let candidate = example_data().recover_password(
+            &settings(),
+            &constraints,
+            &|candidate: &str| candidate == "Cats",
+            usize::MAX,
+        );
+        assert!(candidate.is_none());
+    }
+
+    #[cfg(feature = "bcrypt")]
+    #[test]
+    fn test_recover_password_bcrypt() {
+        let hash = bcrypt::hash("Cat05!", 4).unwrap();
+        let verifier = BcryptVerifier::new(&hash).unwrap();
+        let constraints = RecoveryConstraints {
+            template: Some(vec![
+                TemplateBit::Text("Cats".to_string()),
+                TemplateBit::Number(NumberType::BirthYear),
+                TemplateBit::Category(BitCategory::Symbol),
+            ]),
+            ..Default::default()
+        };
+        let candidate = example_data().recover_password(&settings(), &constraints, &verifier, 100);
+        assert_eq!(candidate.map(|c| c.password), Some("Cat05!".to_string()));
+    }
+
+    #[test]
+    fn test_sha256_verifier_invalid() {
+        assert!(Sha256Verifier::from_hex("not hex").is_err());
+        assert!(Sha256Verifier::from_hex("abcd").is_err());
+    }
+}
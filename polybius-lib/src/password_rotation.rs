@@ -0,0 +1,417 @@
+use alloc::{string::ToString, vec::Vec};
+use core::fmt;
+
+use rand::Rng;
+
+use crate::{
+    password::Password,
+    password_bits::{BitCategory, BitSource, PasswordBit},
+    password_data::{NumberType, PasswordData},
+    password_generation::{Emission, GenerationSettings},
+};
+
+/// Bumping a year forward by at most this much, like 2024 to 2025 or 99 to 00, doesn't count as a rotation
+const TRIVIAL_YEAR_INCREMENT: u32 = 1;
+
+/// What a rotated password keeps from the previous one
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum RotationShape {
+    /// Each position keeps its kind of bit, a number, a text or a symbol
+    #[default]
+    Template,
+    /// Each position also keeps how it was made: numbers keep their type and whether they were truncated, and texts
+    /// keep the length of their prefix
+    Provenance,
+}
+
+/// The settings of a password rotation
+#[derive(Clone, PartialEq, Debug)]
+pub struct RotationSettings {
+    /// What the new password keeps from the previous one
+    pub shape: RotationShape,
+    /// The fewest characters to insert, delete or substitute between the previous password and the new one
+    pub min_edit_distance: usize,
+}
+
+impl Default for RotationSettings {
+    fn default() -> Self {
+        Self {
+            shape: RotationShape::Template,
+            min_edit_distance: 4,
+        }
+    }
+}
+
+/// The reasons a password can't be rotated
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum RotationError {
+    /// The bit at this position can't be generated from the data, so the profile changed since the password was made
+    UnknownBit(usize),
+    /// No password with the same shape was far enough from the previous one within the attempts
+    NoRotation,
+}
+
+impl fmt::Display for RotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RotationError::UnknownBit(position) => write!(
+                f,
+                "The bit number {} can't be made from the data anymore",
+                position + 1
+            ),
+            RotationError::NoRotation => write!(
+                f,
+                "No password with the same shape is different enough from the previous one"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RotationError {}
+
+/// Whether `next` only bumps the years of `previous`, like the current year at the end of a password
+pub fn is_trivial_rotation(previous: &Password, next: &Password) -> bool {
+    if previous.bit_count() != next.bit_count() {
+        return false;
+    }
+    previous
+        .bits()
+        .iter()
+        .zip(next.bits())
+        .all(|(previous, next)| previous == next || is_year_increment(previous, next))
+}
+
+/// Whether `next` is the same birth or current year as `previous`, written the same way, a little later
+fn is_year_increment(previous: &PasswordBit, next: &PasswordBit) -> bool {
+    let is_year = [NumberType::BirthYear, NumberType::CurrentYear]
+        .iter()
+        .any(|year| previous.importance == year.to_string());
+    if !is_year || previous.importance != next.importance || previous.bits.len() != next.bits.len()
+    {
+        return false;
+    }
+    match (previous.bits.parse::<u32>(), next.bits.parse::<u32>()) {
+        (Ok(previous_year), Ok(next_year)) => {
+            // A truncated year wraps around, from 99 to 00
+            let modulus = 10u32.pow(previous.bits.len() as u32);
+            let increment = (next_year + modulus - previous_year % modulus) % modulus;
+            (1..=TRIVIAL_YEAR_INCREMENT).contains(&increment)
+        }
+        _ => false,
+    }
+}
+
+impl RotationShape {
+    /// Whether a bit made from `candidate` keeps the shape of a bit made from `previous`
+    fn keeps(&self, data: &PasswordData, previous: &BitSource, candidate: &BitSource) -> bool {
+        match self {
+            RotationShape::Template => previous.category() == candidate.category(),
+            RotationShape::Provenance => match (previous, candidate) {
+                (
+                    BitSource::Number {
+                        index: previous,
+                        truncated: previous_truncated,
+                    },
+                    BitSource::Number {
+                        index: candidate,
+                        truncated: candidate_truncated,
+                    },
+                ) => {
+                    previous_truncated == candidate_truncated
+                        && data.numbers_poll[*previous].num_type
+                            == data.numbers_poll[*candidate].num_type
+                }
+                (
+                    BitSource::Text {
+                        length: previous, ..
+                    },
+                    BitSource::Text {
+                        length: candidate, ..
+                    },
+                ) => previous == candidate,
+                _ => {
+                    previous.category() == BitCategory::Symbol
+                        && candidate.category() == BitCategory::Symbol
+                }
+            },
+        }
+    }
+}
+
+pub trait PasswordRotation {
+    /// Generates a new password with the same shape as `previous`, to replace it when a site asks for a change.
+    /// `generation` are the settings `previous` was generated with, its symbols coming from their symbol set.
+    /// Passwords too close to the previous one, or that only bump its numbers, are generated again up to
+    /// `max_attempts` times
+    #[cfg(feature = "std")]
+    fn rotate_password(
+        &self,
+        previous: &Password,
+        generation: &GenerationSettings,
+        settings: &RotationSettings,
+        max_attempts: usize,
+    ) -> Result<Password, RotationError> {
+        self.rotate_password_with_rng(
+            previous,
+            generation,
+            settings,
+            max_attempts,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    /// Rotates a password using the given random number generator, for targets without `std`
+    fn rotate_password_with_rng<R: Rng + ?Sized>(
+        &self,
+        previous: &Password,
+        generation: &GenerationSettings,
+        settings: &RotationSettings,
+        max_attempts: usize,
+        rng: &mut R,
+    ) -> Result<Password, RotationError>;
+}
+
+impl PasswordRotation for PasswordData {
+    fn rotate_password_with_rng<R: Rng + ?Sized>(
+        &self,
+        previous: &Password,
+        generation: &GenerationSettings,
+        settings: &RotationSettings,
+        max_attempts: usize,
+        rng: &mut R,
+    ) -> Result<Password, RotationError> {
+        let emissions = self.emissions(generation);
+
+        // Find where each bit came from, the most likely source first when several give the same bit
+        let mut choices: Vec<Vec<&Emission>> = Vec::with_capacity(previous.bit_count());
+        for (position, bit) in previous.bits().iter().enumerate() {
+            let source = emissions
+                .iter()
+                .filter(|e| e.bit == *bit)
+                .max_by(|a, b| a.probability.total_cmp(&b.probability))
+                .ok_or(RotationError::UnknownBit(position))?
+                .source;
+            choices.push(
+                emissions
+                    .iter()
+                    .filter(|e| settings.shape.keeps(self, &source, &e.source))
+                    .collect(),
+            );
+        }
+
+        for _ in 0..max_attempts {
+            let next = Password::new(
+                choices
+                    .iter()
+                    .map(|choices| choose_emission(choices, rng).bit.clone())
+                    .collect(),
+            );
+            if previous.edit_distance(&next) >= settings.min_edit_distance
+                && !is_trivial_rotation(previous, &next)
+            {
+                return Ok(next);
+            }
+        }
+        Err(RotationError::NoRotation)
+    }
+}
+
+/// Picks an emission with the odds the generator would give it. `choices` always has the previous bit's source.
+fn choose_emission<'a, R: Rng + ?Sized>(choices: &[&'a Emission], rng: &mut R) -> &'a Emission {
+    let total: f64 = choices.iter().map(|e| e.probability).sum();
+    let mut target = rng.gen::<f64>() * total;
+    for emission in choices {
+        if target < emission.probability {
+            return emission;
+        }
+        target -= emission.probability;
+    }
+    choices[choices.len() - 1]
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{
+        password_bits::PasswordBit,
+        password_data::{Number, NumberType},
+    };
+
+    use super::*;
+
+    fn data() -> PasswordData {
+        PasswordData::new(
+            [
+                Number::new(2005, NumberType::BirthYear),
+                Number::new(2024, NumberType::CurrentYear),
+                Number::new(12, NumberType::BirthMonth),
+            ]
+            .to_vec(),
+            ["Cats", "Lemons", "Apples", "Oranges"]
+                .map(String::from)
+                .to_vec(),
+        )
+    }
+
+    fn password(bits: &[(&str, &str)]) -> Password {
+        Password::new(
+            bits.iter()
+                .map(|(bits, importance)| {
+                    PasswordBit::new(bits.to_string(), importance.to_string())
+                })
+                .collect(),
+        )
+    }
+
+    fn previous() -> Password {
+        password(&[
+            ("05", "Birth Year"),
+            ("Cat", "Cats"),
+            ("Le", "Lemons"),
+            ("!", "Symbol"),
+            ("2024", "Current Year"),
+        ])
+    }
+
+    #[test]
+    fn test_rotate_keeps_the_template() {
+        let settings = RotationSettings::default();
+        for _ in 0..20 {
+            let next = data()
+                .rotate_password(&previous(), &GenerationSettings::default(), &settings, 100)
+                .unwrap();
+            assert_eq!(next.bit_count(), 5);
+            assert!(previous().edit_distance(&next) >= settings.min_edit_distance);
+            assert!(next.bits()[0].bits.parse::<u32>().is_ok());
+            assert!(next.bits()[1].bits.parse::<u32>().is_err());
+            assert_eq!(next.bits()[3].importance, "Symbol");
+        }
+    }
+
+    #[test]
+    fn test_rotate_keeps_the_provenance() {
+        let settings = RotationSettings {
+            shape: RotationShape::Provenance,
+            min_edit_distance: 3,
+        };
+        for _ in 0..20 {
+            let next = data()
+                .rotate_password(&previous(), &GenerationSettings::default(), &settings, 100)
+                .unwrap();
+            let importances: Vec<&str> = next
+                .bits()
+                .iter()
+                .map(|bit| bit.importance.as_str())
+                .collect();
+            assert_eq!(importances[0], "Birth Year");
+            assert_eq!(importances[4], "Current Year");
+            assert_eq!(next.bits()[0].bits.len(), 2);
+            assert_eq!(next.bits()[1].bits.len(), 3);
+            assert_eq!(next.bits()[2].bits.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_rotate_with_the_symbol_set() {
+        let generation = GenerationSettings::builder().symbol_set(['!', '#']).build();
+        let settings = RotationSettings {
+            shape: RotationShape::Template,
+            min_edit_distance: 1,
+        };
+        for _ in 0..20 {
+            let next = data()
+                .rotate_password(&previous(), &generation, &settings, 100)
+                .unwrap();
+            assert!(["!", "#"].contains(&next.bits()[3].bits.as_str()));
+        }
+
+        // A symbol out of the set can't have been generated with these settings
+        let generation = GenerationSettings::builder().symbol_set(['#']).build();
+        assert_eq!(
+            data().rotate_password(&previous(), &generation, &settings, 10),
+            Err(RotationError::UnknownBit(3))
+        );
+    }
+
+    #[test]
+    fn test_trivial_rotation() {
+        let bumped = password(&[
+            ("05", "Birth Year"),
+            ("Cat", "Cats"),
+            ("Le", "Lemons"),
+            ("!", "Symbol"),
+            ("2025", "Current Year"),
+        ]);
+        assert!(is_trivial_rotation(&previous(), &bumped));
+        assert!(!is_trivial_rotation(
+            &previous(),
+            &password(&[
+                ("2024", "Current Year"),
+                ("Cat", "Cats"),
+                ("Le", "Lemons"),
+                ("!", "Symbol"),
+                ("05", "Birth Year"),
+            ])
+        ));
+
+        // Truncated years wrap around
+        assert!(is_trivial_rotation(
+            &password(&[("99", "Birth Year")]),
+            &password(&[("00", "Birth Year")])
+        ));
+        // Going back, changing the kind of number or the way it is written is not a bump
+        for (previous, next) in [
+            (("2025", "Current Year"), ("2024", "Current Year")),
+            (("12", "Birth Month"), ("13", "Birth Month")),
+            (("12", "Birth Month"), ("14", "Birth Day")),
+            (("2024", "Current Year"), ("2025", "Relevant Number")),
+            (("2024", "Current Year"), ("2026", "Current Year")),
+            (("99", "Birth Year"), ("2000", "Birth Year")),
+        ] {
+            assert!(
+                !is_trivial_rotation(&password(&[previous]), &password(&[next])),
+                "{:?} to {:?}",
+                previous,
+                next
+            );
+        }
+
+        // The only other password bumps the current year
+        let data = PasswordData::new(
+            [
+                Number::new(2024, NumberType::CurrentYear),
+                Number::new(2025, NumberType::CurrentYear),
+            ]
+            .to_vec(),
+            ["Cats".to_string()].to_vec(),
+        );
+        let previous = password(&[("Cat", "Cats"), ("2024", "Current Year")]);
+        let settings = RotationSettings {
+            shape: RotationShape::Provenance,
+            min_edit_distance: 1,
+        };
+        assert_eq!(
+            data.rotate_password(&previous, &GenerationSettings::default(), &settings, 100),
+            Err(RotationError::NoRotation)
+        );
+        let previous = password(&[("Cat", "Cats"), ("2025", "Current Year")]);
+        assert!(data
+            .rotate_password(&previous, &GenerationSettings::default(), &settings, 100)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_rotate_unknown_bit() {
+        let previous = password(&[("Cat", "Cats"), ("Dog", "Dogs")]);
+        assert_eq!(
+            data().rotate_password(
+                &previous,
+                &GenerationSettings::default(),
+                &RotationSettings::default(),
+                10
+            ),
+            Err(RotationError::UnknownBit(1))
+        );
+    }
+}
@@ -0,0 +1,524 @@
+//! Support for the `passwordrules` format sites use to publish their password requirements, like
+//! `required: upper; required: digit; allowed: [-().&@?'#,/"+]; max-consecutive: 2; minlength: 8; maxlength: 16`
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use rand::Rng;
+
+use crate::{
+    password::Password,
+    password_data::PasswordData,
+    password_generation::{GenerationSettings, PasswordGeneration},
+};
+
+/// A set of characters a rule can require or allow
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CharacterClass {
+    Upper,
+    Lower,
+    Digit,
+    /// The ASCII printable characters that aren't letters nor digits, space included
+    Special,
+    AsciiPrintable,
+    Unicode,
+    /// The characters listed between square brackets
+    Custom(Vec<char>),
+}
+
+impl CharacterClass {
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            CharacterClass::Upper => c.is_ascii_uppercase(),
+            CharacterClass::Lower => c.is_ascii_lowercase(),
+            CharacterClass::Digit => c.is_ascii_digit(),
+            CharacterClass::Special => c == ' ' || c.is_ascii_punctuation(),
+            CharacterClass::AsciiPrintable => (' '..='~').contains(&c),
+            CharacterClass::Unicode => true,
+            CharacterClass::Custom(chars) => chars.contains(&c),
+        }
+    }
+}
+
+/// The requirements of a site for its passwords
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PasswordRules {
+    /// Each entry needs at least one character from one of its classes
+    pub required: Vec<Vec<CharacterClass>>,
+    /// The classes allowed on top of the required ones. When both are empty, every ASCII printable character is allowed
+    pub allowed: Vec<CharacterClass>,
+    /// The most times the same character can be repeated in a row
+    pub max_consecutive: Option<usize>,
+    /// The shortest password allowed, in characters
+    pub min_length: Option<usize>,
+    /// The longest password allowed, in characters
+    pub max_length: Option<usize>,
+}
+
+/// A part of the rules that was ignored while parsing them
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum RuleDiagnostic {
+    /// A rule that isn't in the format or that Polybius doesn't support
+    UnsupportedRule(String),
+    /// A character class that isn't in the format
+    UnknownClass(String),
+    /// A rule whose value can't be used, like a length that isn't a number
+    InvalidValue { rule: String, value: String },
+    /// A rule that isn't a `name: value` pair
+    Malformed(String),
+    /// The minimum length is over the maximum length
+    ConflictingLengths { min: usize, max: usize },
+}
+
+impl fmt::Display for RuleDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleDiagnostic::UnsupportedRule(rule) => {
+                write!(f, "The rule {} is not supported", rule)
+            }
+            RuleDiagnostic::UnknownClass(class) => {
+                write!(f, "The character class {} is not known", class)
+            }
+            RuleDiagnostic::InvalidValue { rule, value } => {
+                write!(f, "The value {} is not valid for the rule {}", value, rule)
+            }
+            RuleDiagnostic::Malformed(rule) => write!(f, "The rule {} has no value", rule),
+            RuleDiagnostic::ConflictingLengths { min, max } => write!(
+                f,
+                "The minimum length {} is over the maximum length {}",
+                min, max
+            ),
+        }
+    }
+}
+
+/// The first rule a password breaks
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum RuleViolation {
+    TooShort,
+    TooLong,
+    /// The required entry at this index has no character in the password
+    MissingRequired(usize),
+    /// The password has a character that isn't allowed
+    NotAllowed,
+    /// The same character is repeated too many times in a row
+    TooManyConsecutive,
+    /// No password was generated to check, as no attempt was allowed
+    NoAttempts,
+}
+
+impl fmt::Display for RuleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleViolation::TooShort => write!(f, "The password is too short"),
+            RuleViolation::TooLong => write!(f, "The password is too long"),
+            RuleViolation::MissingRequired(index) => write!(
+                f,
+                "The password is missing a character required by rule number {}",
+                index + 1
+            ),
+            RuleViolation::NotAllowed => {
+                write!(f, "The password has a character that isn't allowed")
+            }
+            RuleViolation::TooManyConsecutive => {
+                write!(
+                    f,
+                    "The password repeats a character too many times in a row"
+                )
+            }
+            RuleViolation::NoAttempts => write!(f, "No attempt was allowed to generate a password"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RuleViolation {}
+
+impl PasswordRules {
+    /// Parses rules in the `passwordrules` format. The rules that can't be used are left out and reported.
+    /// Values copied from HTML may keep their `&quot;` like entities, they are decoded.
+    pub fn parse(rules: &str) -> (Self, Vec<RuleDiagnostic>) {
+        let rules = decode_entities(rules);
+        let mut parsed = Self::default();
+        let mut diagnostics = Vec::new();
+
+        for rule in split_outside_brackets(&rules, ';') {
+            let rule = rule.trim();
+            if rule.is_empty() {
+                continue;
+            }
+            let Some((name, value)) = rule.split_once(':') else {
+                diagnostics.push(RuleDiagnostic::Malformed(rule.to_string()));
+                continue;
+            };
+            let name = name.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            match name.as_str() {
+                "required" | "allowed" => {
+                    let classes = parse_classes(value, &mut diagnostics);
+                    if name == "required" {
+                        if !classes.is_empty() {
+                            parsed.required.push(classes);
+                        }
+                    } else {
+                        parsed.allowed.extend(classes);
+                    }
+                }
+                "max-consecutive" | "minlength" | "maxlength" => {
+                    let Ok(number) = value.parse::<usize>() else {
+                        diagnostics.push(RuleDiagnostic::InvalidValue {
+                            rule: name,
+                            value: value.to_string(),
+                        });
+                        continue;
+                    };
+                    // Repeated rules keep the strictest value
+                    let (field, strictest): (_, fn(usize, usize) -> usize) = match name.as_str() {
+                        "max-consecutive" => (&mut parsed.max_consecutive, usize::min),
+                        "minlength" => (&mut parsed.min_length, usize::max),
+                        _ => (&mut parsed.max_length, usize::min),
+                    };
+                    *field = Some(field.map_or(number, |current| strictest(current, number)));
+                }
+                _ => diagnostics.push(RuleDiagnostic::UnsupportedRule(name)),
+            }
+        }
+
+        if let (Some(min), Some(max)) = (parsed.min_length, parsed.max_length) {
+            if min > max {
+                diagnostics.push(RuleDiagnostic::ConflictingLengths { min, max });
+            }
+        }
+        (parsed, diagnostics)
+    }
+
+    /// Whether a character can be in the password
+    pub fn allows(&self, c: char) -> bool {
+        if self.required.is_empty() && self.allowed.is_empty() {
+            return CharacterClass::AsciiPrintable.contains(c);
+        }
+        self.allowed
+            .iter()
+            .chain(self.required.iter().flatten())
+            .any(|class| class.contains(c))
+    }
+
+    /// Checks a password follows the rules
+    pub fn check(&self, password: &Password) -> Result<(), RuleViolation> {
+        let password = password.expose_secret();
+        let length = password.chars().count();
+        if self.min_length.is_some_and(|min| length < min) {
+            return Err(RuleViolation::TooShort);
+        }
+        if self.max_length.is_some_and(|max| length > max) {
+            return Err(RuleViolation::TooLong);
+        }
+        if !password.chars().all(|c| self.allows(c)) {
+            return Err(RuleViolation::NotAllowed);
+        }
+        if let Some(index) = self.required.iter().position(|classes| {
+            !password
+                .chars()
+                .any(|c| classes.iter().any(|class| class.contains(c)))
+        }) {
+            return Err(RuleViolation::MissingRequired(index));
+        }
+        if let Some(max) = self.max_consecutive {
+            let mut previous = None;
+            let mut run = 0;
+            for c in password.chars() {
+                run = if previous == Some(c) { run + 1 } else { 1 };
+                previous = Some(c);
+                if run > max {
+                    return Err(RuleViolation::TooManyConsecutive);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The settings with their symbol set narrowed to the symbols these rules allow
+    pub fn restrict(&self, settings: &GenerationSettings) -> GenerationSettings {
+        let mut settings = settings.clone();
+        settings.symbol_set.retain(|&symbol| self.allows(symbol));
+        settings
+    }
+
+    /// The settings with their length in bits kept in the bounds the lengths in characters allow, given the shortest
+    /// and the longest bits the data can give
+    fn fit_length(&self, data: &PasswordData, settings: &GenerationSettings) -> GenerationSettings {
+        let mut settings = settings.clone();
+        let lengths: Vec<usize> = data
+            .emissions(&settings)
+            .iter()
+            .map(|emission| emission.bit.bits.chars().count().max(1))
+            .collect();
+        let (Some(&shortest), Some(&longest)) = (lengths.iter().min(), lengths.iter().max()) else {
+            return settings;
+        };
+        if let Some(max) = self.max_length {
+            settings.length = settings.length.min((max / shortest).max(1));
+        }
+        if let Some(min) = self.min_length {
+            settings.length = settings.length.max(min.div_ceil(longest));
+        }
+        settings
+    }
+}
+
+/// Decodes the HTML entities a `passwordrules` attribute value can have when copied from a page source
+fn decode_entities(rules: &str) -> String {
+    rules
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Splits on `separator`, except inside a custom class where it is a character of the class
+fn split_outside_brackets(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_brackets = false;
+    for (index, c) in value.char_indices() {
+        match c {
+            '[' if !in_brackets => in_brackets = true,
+            // A `]` right after the `[` is a character of the class
+            ']' if in_brackets && !value[..index].ends_with('[') => in_brackets = false,
+            c if c == separator && !in_brackets => {
+                parts.push(&value[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+fn parse_classes(value: &str, diagnostics: &mut Vec<RuleDiagnostic>) -> Vec<CharacterClass> {
+    let mut classes = Vec::new();
+    for class in split_outside_brackets(value, ',') {
+        let class = class.trim();
+        let parsed = match class.to_ascii_lowercase().as_str() {
+            "" => continue,
+            "upper" => CharacterClass::Upper,
+            "lower" => CharacterClass::Lower,
+            "digit" => CharacterClass::Digit,
+            "special" => CharacterClass::Special,
+            "ascii-printable" => CharacterClass::AsciiPrintable,
+            "unicode" => CharacterClass::Unicode,
+            _ => match class.strip_prefix('[').and_then(|c| c.strip_suffix(']')) {
+                Some(chars) => CharacterClass::Custom(chars.chars().collect()),
+                None => {
+                    diagnostics.push(RuleDiagnostic::UnknownClass(class.to_string()));
+                    continue;
+                }
+            },
+        };
+        classes.push(parsed);
+    }
+    classes
+}
+
+pub trait RuleAwareGeneration {
+    /// Generates a password that follows the rules
+    /// Only the symbols the rules allow are used, and passwords breaking the other rules are generated again, up to
+    /// `max_attempts` times. The lengths of the rules are in characters while the length of the settings is in bits,
+    /// so the bits are fewer when even the shortest ones would be too long, and more when even the longest ones would
+    /// be too short.
+    #[cfg(feature = "std")]
+    fn generate_password_with_rules(
+        &self,
+        settings: &GenerationSettings,
+        rules: &PasswordRules,
+        max_attempts: usize,
+    ) -> Result<Password, RuleViolation> {
+        self.generate_password_with_rules_and_rng(
+            settings,
+            rules,
+            max_attempts,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    /// Generates a password that follows the rules using the given random number generator, for targets without
+    /// `std`. Returns the violation of the last attempt when none follows the rules.
+    fn generate_password_with_rules_and_rng<R: Rng + ?Sized>(
+        &self,
+        settings: &GenerationSettings,
+        rules: &PasswordRules,
+        max_attempts: usize,
+        rng: &mut R,
+    ) -> Result<Password, RuleViolation>;
+}
+
+impl RuleAwareGeneration for PasswordData {
+    fn generate_password_with_rules_and_rng<R: Rng + ?Sized>(
+        &self,
+        settings: &GenerationSettings,
+        rules: &PasswordRules,
+        max_attempts: usize,
+        rng: &mut R,
+    ) -> Result<Password, RuleViolation> {
+        let settings = rules.fit_length(self, &rules.restrict(settings));
+        let mut violation = RuleViolation::NoAttempts;
+        for _ in 0..max_attempts {
+            let password = self.generate_password_with_rng(&settings, rng);
+            match rules.check(&password) {
+                Ok(()) => return Ok(password),
+                Err(error) => violation = error,
+            }
+        }
+        Err(violation)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{
+        password_bits::PasswordBit,
+        password_data::{Number, NumberType, PasswordData},
+    };
+
+    use super::*;
+
+    fn password(bits: &str) -> Password {
+        Password::new(vec![PasswordBit::new(bits.to_string(), "Test".to_string())])
+    }
+
+    #[test]
+    fn test_parse() {
+        let (rules, diagnostics) = PasswordRules::parse(
+            "required: upper; required: digit; allowed: [-().&@?'#,/&quot;+]; max-consecutive: 2; minlength: 8; maxlength: 16",
+        );
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(
+            rules.required,
+            vec![vec![CharacterClass::Upper], vec![CharacterClass::Digit]]
+        );
+        assert_eq!(
+            rules.allowed,
+            vec![CharacterClass::Custom("-().&@?'#,/\"+".chars().collect())]
+        );
+        assert_eq!(rules.max_consecutive, Some(2));
+        assert_eq!(rules.min_length, Some(8));
+        assert_eq!(rules.max_length, Some(16));
+    }
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let (rules, diagnostics) = PasswordRules::parse(
+            "required: lower, upper; allowed: emoji; minlength: eight; maxlength: 4; minlength: 6; passwordhint: x; nonsense",
+        );
+        assert_eq!(
+            rules.required,
+            vec![vec![CharacterClass::Lower, CharacterClass::Upper]]
+        );
+        assert!(rules.allowed.is_empty());
+        assert_eq!(
+            diagnostics,
+            vec![
+                RuleDiagnostic::UnknownClass("emoji".to_string()),
+                RuleDiagnostic::InvalidValue {
+                    rule: "minlength".to_string(),
+                    value: "eight".to_string()
+                },
+                RuleDiagnostic::UnsupportedRule("passwordhint".to_string()),
+                RuleDiagnostic::Malformed("nonsense".to_string()),
+                RuleDiagnostic::ConflictingLengths { min: 6, max: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let (rules, _) = PasswordRules::parse(
+            "required: upper; required: digit; allowed: lower, [!]; max-consecutive: 2; minlength: 6; maxlength: 10",
+        );
+        assert_eq!(rules.check(&password("Cat05!")), Ok(()));
+        assert_eq!(rules.check(&password("Ca05")), Err(RuleViolation::TooShort));
+        assert_eq!(
+            rules.check(&password("Cat05!Cat05!")),
+            Err(RuleViolation::TooLong)
+        );
+        assert_eq!(
+            rules.check(&password("Cat05#")),
+            Err(RuleViolation::NotAllowed)
+        );
+        assert_eq!(
+            rules.check(&password("Cat!!!")),
+            Err(RuleViolation::MissingRequired(1))
+        );
+        assert_eq!(
+            rules.check(&password("Caaat05")),
+            Err(RuleViolation::TooManyConsecutive)
+        );
+    }
+
+    #[test]
+    fn test_generate_password_with_rules() {
+        let password_data = PasswordData::new(
+            [Number::new(2005, NumberType::BirthYear)].to_vec(),
+            ["Cats", "Lemons"].map(String::from).to_vec(),
+        );
+        let settings = GenerationSettings::builder().length(6).build();
+        let (rules, _) = PasswordRules::parse(
+            "required: upper; required: digit; required: [&(]; allowed: lower; max-consecutive: 2; maxlength: 16",
+        );
+        assert_eq!(rules.restrict(&settings).symbol_set, vec!['&', '(']);
+
+        let password = password_data
+            .generate_password_with_rules(&settings, &rules, 10_000)
+            .unwrap();
+        assert_eq!(rules.check(&password), Ok(()));
+
+        let (rules, _) = PasswordRules::parse("required: [.]; allowed: ascii-printable");
+        assert_eq!(
+            password_data.generate_password_with_rules(&settings, &rules, 10),
+            Err(RuleViolation::MissingRequired(0))
+        );
+        assert_eq!(
+            password_data.generate_password_with_rules(&settings, &rules, 0),
+            Err(RuleViolation::NoAttempts)
+        );
+    }
+
+    #[test]
+    fn test_generate_password_with_rules_lengths() {
+        let password_data = PasswordData::new(
+            [Number::new(2005, NumberType::BirthYear)].to_vec(),
+            ["Cats", "Lemons"].map(String::from).to_vec(),
+        );
+        let settings = GenerationSettings::builder().length(6).build();
+
+        // Six bits have at least six characters
+        let (rules, _) = PasswordRules::parse("maxlength: 4");
+        assert_eq!(rules.fit_length(&password_data, &settings).length, 4);
+        let password = password_data
+            .generate_password_with_rules(&settings, &rules, 10_000)
+            .unwrap();
+        assert_eq!(rules.check(&password), Ok(()));
+
+        // Bits have at most four characters, like 2005, so two bits are too short
+        let short_settings = GenerationSettings::builder().length(2).build();
+        let (rules, _) = PasswordRules::parse("minlength: 12");
+        assert_eq!(rules.fit_length(&password_data, &short_settings).length, 3);
+        let password = password_data
+            .generate_password_with_rules(&short_settings, &rules, 10_000)
+            .unwrap();
+        assert_eq!(rules.check(&password), Ok(()));
+
+        // Lengths the settings already fit in are kept
+        let (rules, _) = PasswordRules::parse("minlength: 6; maxlength: 24");
+        assert_eq!(rules.fit_length(&password_data, &settings).length, 6);
+    }
+}
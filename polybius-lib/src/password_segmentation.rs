@@ -0,0 +1,410 @@
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{
+    password_bits::{BitSource, PasswordBits},
+    password_data::PasswordData,
+    password_generation::{Emission, GenerationSettings},
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The most segmentations `segment_password` returns, as repetitive data can split a password in exponentially many ways
+pub const MAX_SEGMENTATIONS: usize = 10_000;
+
+/// A way to split a password into the bits the generator could have produced it from
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Segmentation {
+    /// The bits, in order, that concatenate back to the password
+    pub bits: PasswordBits,
+    /// Where each bit comes from, in the same order as `bits`
+    pub sources: Vec<BitSource>,
+    /// The probability of the generator emitting exactly these bits, the other positions being skipped
+    pub probability: f64,
+}
+
+/// The reasons a password can't be segmented
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum SegmentationError {
+    /// The password splits in more ways than the limit
+    TooManySegmentations(usize),
+}
+
+impl fmt::Display for SegmentationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SegmentationError::TooManySegmentations(limit) => {
+                write!(f, "The password can be split in more than {} ways", limit)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SegmentationError {}
+
+pub trait PasswordSegmentation {
+    /// Splits a password back into bits, the most likely first
+    ///
+    /// Every sequence of bits the generator could emit in `settings.length` positions that concatenates to the password
+    /// is returned. Fails when there are more than `MAX_SEGMENTATIONS` of them.
+    fn segment_password(
+        &self,
+        password: &str,
+        settings: &GenerationSettings,
+    ) -> Result<Vec<Segmentation>, SegmentationError> {
+        self.segment_password_with_limit(password, settings, MAX_SEGMENTATIONS)
+    }
+
+    /// Splits a password back into bits, failing when there are more than `max_results` ways to
+    fn segment_password_with_limit(
+        &self,
+        password: &str,
+        settings: &GenerationSettings,
+        max_results: usize,
+    ) -> Result<Vec<Segmentation>, SegmentationError>;
+
+    /// The probability of the generator producing the password, summed over all its segmentations
+    ///
+    /// Unlike `segment_password` it doesn't list them, so it takes polynomial time however repetitive the data is.
+    fn password_probability(&self, password: &str, settings: &GenerationSettings) -> f64;
+}
+
+/// The bits the generator can emit at each position, and how likely a position is to be skipped
+struct Model {
+    /// The emissions of non-empty bits, as empty ones can't be seen in the password
+    emissions: Vec<Emission>,
+    /// The odds of a position emitting nothing, when the poll of its category is empty or its bit is empty
+    skip: f64,
+    length: usize,
+}
+
+impl Model {
+    fn new(data: &PasswordData, settings: &GenerationSettings) -> Self {
+        let emissions: Vec<Emission> = data
+            .emissions(settings)
+            .into_iter()
+            .filter(|e| !e.bit.bits.is_empty())
+            .collect();
+        let skip = 1.0 - emissions.iter().map(|e| e.probability).sum::<f64>();
+        Self {
+            emissions,
+            // Rounding errors aside, there is nothing to skip
+            skip: if skip > 1e-12 { skip } else { 0.0 },
+            length: settings.length,
+        }
+    }
+
+    /// The emissions matching the password at each byte offset, along with where they end
+    fn matches(&self, password: &str) -> Vec<Vec<(&Emission, usize)>> {
+        (0..password.len())
+            .map(|offset| match password.get(offset..) {
+                Some(rest) => self
+                    .emissions
+                    .iter()
+                    .filter(|e| rest.starts_with(e.bit.bits.as_str()))
+                    .map(|e| (e, offset + e.bit.bits.len()))
+                    .collect(),
+                None => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// The odds of the skipped positions when `bits` positions out of `length` emitted a bit, in any of them
+    fn skip_odds(&self, bits: usize) -> f64 {
+        if bits > self.length {
+            return 0.0;
+        }
+        let skipped = self.length - bits;
+        // C(length, bits) * skip^skipped
+        let mut odds = 1.0;
+        for i in 0..skipped {
+            odds *= self.skip * (self.length - i) as f64 / (skipped - i) as f64;
+        }
+        odds
+    }
+
+    /// Whether a password of this many bits can come out of the generator
+    fn allows(&self, bits: usize) -> bool {
+        bits == self.length || (bits < self.length && self.skip > 0.0)
+    }
+}
+
+impl PasswordSegmentation for PasswordData {
+    fn segment_password_with_limit(
+        &self,
+        password: &str,
+        settings: &GenerationSettings,
+        max_results: usize,
+    ) -> Result<Vec<Segmentation>, SegmentationError> {
+        let model = Model::new(self, settings);
+        let matches = model.matches(password);
+
+        // The numbers of bits the rest of the password from each offset can be split into, to only walk complete paths
+        let mut splits = vec![vec![false; model.length + 1]; password.len() + 1];
+        splits[password.len()][0] = true;
+        for offset in (0..password.len()).rev() {
+            for &(_, end) in &matches[offset] {
+                for bits in 0..model.length {
+                    if splits[end][bits] {
+                        splits[offset][bits + 1] = true;
+                    }
+                }
+            }
+        }
+
+        let mut walk = Walk {
+            model: &model,
+            matches: &matches,
+            splits: &splits,
+            path: Vec::new(),
+            segmentations: Vec::new(),
+            max_results,
+        };
+        walk.segment(0)?;
+
+        let mut segmentations = walk.segmentations;
+        segmentations.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+        Ok(segmentations)
+    }
+
+    fn password_probability(&self, password: &str, settings: &GenerationSettings) -> f64 {
+        let model = Model::new(self, settings);
+        let matches = model.matches(password);
+
+        // The probability of emitting the password up to each offset in each number of bits
+        let mut reached = vec![vec![0.0; model.length + 1]; password.len() + 1];
+        reached[0][0] = 1.0;
+        for offset in 0..password.len() {
+            for &(emission, end) in &matches[offset] {
+                for bits in 0..model.length {
+                    let probability = reached[offset][bits];
+                    if probability > 0.0 {
+                        reached[end][bits + 1] += probability * emission.probability;
+                    }
+                }
+            }
+        }
+
+        reached[password.len()]
+            .iter()
+            .enumerate()
+            .filter(|&(bits, _)| model.allows(bits))
+            .map(|(bits, probability)| probability * model.skip_odds(bits))
+            .sum()
+    }
+}
+
+/// The state of the walk over every complete segmentation
+struct Walk<'a> {
+    model: &'a Model,
+    matches: &'a [Vec<(&'a Emission, usize)>],
+    splits: &'a [Vec<bool>],
+    path: Vec<&'a Emission>,
+    segmentations: Vec<Segmentation>,
+    max_results: usize,
+}
+
+impl<'a> Walk<'a> {
+    /// Walks every way the password from `offset` can be split, pushing the complete ones to `segmentations`
+    fn segment(&mut self, offset: usize) -> Result<(), SegmentationError> {
+        if offset == self.splits.len() - 1 {
+            if self.segmentations.len() == self.max_results {
+                return Err(SegmentationError::TooManySegmentations(self.max_results));
+            }
+            let bits = self.path.len();
+            if !self.model.allows(bits) {
+                return Ok(());
+            }
+            self.segmentations.push(Segmentation {
+                bits: self.path.iter().map(|e| e.bit.clone()).collect(),
+                sources: self.path.iter().map(|e| e.source).collect(),
+                probability: self.path.iter().map(|e| e.probability).product::<f64>()
+                    * self.model.skip_odds(bits),
+            });
+            return Ok(());
+        }
+
+        let matches = self.matches;
+        for &(emission, end) in &matches[offset] {
+            // Only go on when the rest can be split into a number of bits the generator allows
+            let used = self.path.len() + 1;
+            let completes = self.splits[end]
+                .iter()
+                .enumerate()
+                .any(|(bits, &possible)| possible && self.model.allows(used + bits));
+            if completes {
+                self.path.push(emission);
+                self.segment(end)?;
+                self.path.pop();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::password_data::{Number, NumberType};
+
+    use super::*;
+
+    fn example_data() -> PasswordData {
+        PasswordData::new(
+            [
+                Number::new(2005, NumberType::BirthYear),
+                Number::new(12, NumberType::BirthMonth),
+                Number::new(14, NumberType::BirthDay),
+            ]
+            .to_vec(),
+            [
+                "Apples", "Bananas", "Oranges", "Lemons", "Cats", "Dogs", "Lover",
+            ]
+            .map(String::from)
+            .to_vec(),
+        )
+    }
+
+    #[test]
+    fn test_segment_password_readme_example() {
+        let settings = GenerationSettings::builder()
+            .length(8)
+            .symbols(true)
+            .build();
+        let segmentations = example_data()
+            .segment_password("05CatLeApp05Ora12A", &settings)
+            .unwrap();
+        assert_eq!(segmentations.len(), 1);
+
+        let importances: Vec<&str> = segmentations[0]
+            .bits
+            .iter()
+            .map(|bit| bit.importance.as_str())
+            .collect();
+        assert_eq!(
+            importances,
+            vec![
+                "Birth Year",
+                "Cats",
+                "Lemons",
+                "Apples",
+                "Birth Year",
+                "Oranges",
+                "Birth Month",
+                "Apples"
+            ]
+        );
+        assert_eq!(
+            segmentations[0].sources[0],
+            BitSource::Number {
+                index: 0,
+                truncated: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_segment_password_ranked() {
+        let password_data = PasswordData::new(vec![], ["ab", "a", "b"].map(String::from).to_vec());
+        let settings = GenerationSettings::builder()
+            .length(4)
+            .symbols(false)
+            .build();
+        let segmentations = password_data.segment_password("ab", &settings).unwrap();
+        // "ab" whole, "a" + "b" and the "a" of "ab" + "b"
+        assert_eq!(segmentations.len(), 3);
+        for pair in segmentations.windows(2) {
+            assert!(pair[0].probability >= pair[1].probability);
+        }
+    }
+
+    #[test]
+    fn test_segment_password_respects_length() {
+        let settings = GenerationSettings::builder()
+            .length(2)
+            .symbols(true)
+            .build();
+        let segmentations = example_data()
+            .segment_password("05CatLeApp05Ora12A", &settings)
+            .unwrap();
+        assert!(segmentations.is_empty());
+    }
+
+    #[test]
+    fn test_segment_password_no_symbols() {
+        let settings = GenerationSettings::builder()
+            .length(8)
+            .symbols(false)
+            .build();
+        let segmentations = example_data().segment_password("Cat!", &settings).unwrap();
+        assert!(segmentations.is_empty());
+    }
+
+    #[test]
+    fn test_segment_password_exact_length() {
+        // Both polls have entries, so the generator always emits 8 bits and "Cat" alone can't come out of it
+        let settings = GenerationSettings::builder()
+            .length(8)
+            .symbols(false)
+            .build();
+        let password_data = example_data();
+        assert!(password_data
+            .segment_password("Cat", &settings)
+            .unwrap()
+            .is_empty());
+        assert_eq!(password_data.password_probability("Cat", &settings), 0.0);
+
+        // Without numbers, their positions are skipped, so fewer bits are possible
+        let password_data = PasswordData::new(vec![], vec!["Cats".to_string()]);
+        let segmentations = password_data.segment_password("Cat", &settings).unwrap();
+        assert_eq!(segmentations.len(), 1);
+        assert!(segmentations[0].probability > 0.0);
+    }
+
+    #[test]
+    fn test_password_probability_sums_segmentations() {
+        let password_data = PasswordData::new(
+            vec![Number::new(12, NumberType::BirthMonth)],
+            ["ab", "a", "b"].map(String::from).to_vec(),
+        );
+        let settings = GenerationSettings::builder()
+            .length(4)
+            .symbols(true)
+            .build();
+        for password in ["ab12!", "aab!", "12aab", "abab"] {
+            let sum: f64 = password_data
+                .segment_password(password, &settings)
+                .unwrap()
+                .iter()
+                .map(|segmentation| segmentation.probability)
+                .sum();
+            let probability = password_data.password_probability(password, &settings);
+            assert!(probability > 0.0);
+            assert!((sum - probability).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_segment_password_limit() {
+        // Every split of a run of "a"s works, so there are exponentially many
+        let password_data = PasswordData::new(vec![], vec!["aaa".to_string()]);
+        let settings = GenerationSettings::builder()
+            .length(26)
+            .symbols(false)
+            .build();
+        let password = "a".repeat(26);
+        assert_eq!(
+            password_data.segment_password(&password, &settings),
+            Err(SegmentationError::TooManySegmentations(MAX_SEGMENTATIONS))
+        );
+        assert!(password_data.password_probability(&password, &settings) > 0.0);
+        assert_eq!(
+            password_data
+                .segment_password_with_limit("aaa", &settings, 100)
+                .map(|segmentations| segmentations.len()),
+            Ok(4)
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    password_data::PasswordData, password_generation::GenerationSettings,
+    password_segmentation::PasswordSegmentation,
+};
+
+/// How hard a password is to guess, in bits of entropy
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Strength {
+    /// Against an attacker that only knows which kinds of characters the password uses
+    pub generic_entropy: f64,
+    /// Against an attacker that knows the `PasswordData` and how the generator works.
+    /// None if the generator can't produce the password from the data.
+    pub targeted_entropy: Option<f64>,
+}
+
+/// Returns the bits of entropy of a password against a brute force attacker
+/// The attacker is assumed to know the kinds of characters used: lowercase, uppercase, digits, ASCII symbols and anything else.
+pub fn generic_entropy(password: &str) -> f64 {
+    let mut pool = 0;
+    if password.chars().any(|c| c.is_ascii_lowercase()) {
+        pool += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_uppercase()) {
+        pool += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_digit()) {
+        pool += 10;
+    }
+    // Printable ASCII punctuation, with the space
+    if password
+        .chars()
+        .any(|c| c.is_ascii_punctuation() || c == ' ')
+    {
+        pool += 33;
+    }
+    if !password.is_ascii() {
+        pool += 100;
+    }
+
+    if pool == 0 {
+        return 0.0;
+    }
+    password.chars().count() as f64 * (pool as f64).log2()
+}
+
+pub trait PasswordStrength {
+    /// Returns the bits of entropy of the generator output for these settings
+    /// It is an upper bound, as different bits can concatenate to the same password.
+    fn generation_entropy(&self, settings: &GenerationSettings) -> f64;
+
+    /// Rates a password, generated or not, against both a generic and a targeted attacker
+    fn password_strength(&self, password: &str, settings: &GenerationSettings) -> Strength;
+}
+
+impl PasswordStrength for PasswordData {
+    fn generation_entropy(&self, settings: &GenerationSettings) -> f64 {
+        // Bits from different sources can be the same string, like a birth day and a birth month
+        let mut outcomes: HashMap<&str, f64> = HashMap::new();
+        let emissions = self.emissions(settings);
+        for emission in &emissions {
+            *outcomes.entry(emission.bit.bits.as_str()).or_default() += emission.probability;
+        }
+        // An empty poll makes the generator skip the position instead
+        let skipped = 1.0 - emissions.iter().map(|e| e.probability).sum::<f64>();
+        if skipped > 1e-12 {
+            *outcomes.entry("").or_default() += skipped;
+        }
+
+        let position_entropy: f64 = outcomes
+            .values()
+            .filter(|&&p| p > 0.0)
+            .map(|&p| -p * p.log2())
+            .sum();
+        position_entropy * settings.length as f64
+    }
+
+    fn password_strength(&self, password: &str, settings: &GenerationSettings) -> Strength {
+        let probability = self.password_probability(password, settings);
+
+        Strength {
+            generic_entropy: generic_entropy(password),
+            targeted_entropy: (probability > 0.0).then(|| -probability.min(1.0).log2()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::password_data::{Number, NumberType};
+
+    use super::*;
+
+    #[test]
+    fn test_generic_entropy() {
+        assert_eq!(generic_entropy(""), 0.0);
+        assert!((generic_entropy("abcd") - 4.0 * 26f64.log2()).abs() < 1e-9);
+        assert!((generic_entropy("aB3!") - 4.0 * 95f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_generation_entropy() {
+        // Both polls can only make a "7", so the generator always outputs the same password
+        let password_data = PasswordData::new(
+            vec![Number::new(7, NumberType::RelevantNumber)],
+            vec!["7".to_string()],
+        );
+        let settings = GenerationSettings::builder()
+            .length(8)
+            .symbols(false)
+            .build();
+        assert_eq!(password_data.generation_entropy(&settings), 0.0);
+
+        // 14 equally likely symbols, "a" half of the time and nothing when a number is picked
+        let settings = GenerationSettings::builder()
+            .length(2)
+            .symbols(true)
+            .build();
+        let password_data = PasswordData::new(vec![], vec!["a".to_string()]);
+        let expected =
+            2.0 * (-0.5 * 0.5f64.log2() - 0.25 * 0.25f64.log2() - 0.25 * (0.25f64 / 14.0).log2());
+        assert!((password_data.generation_entropy(&settings) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_password_strength() {
+        let password_data = PasswordData::new(
+            vec![Number::new(12, NumberType::BirthMonth)],
+            vec!["Cats".to_string()],
+        );
+        let settings = GenerationSettings::builder()
+            .length(2)
+            .symbols(false)
+            .build();
+
+        let strength = password_data.password_strength("Cat12", &settings);
+        // 2/3 * 1/3 for "Cat" then 1/3 for "12"
+        let expected = -(2.0f64 / 9.0 / 3.0).log2();
+        assert!((strength.targeted_entropy.unwrap() - expected).abs() < 1e-9);
+        assert!(strength.generic_entropy > strength.targeted_entropy.unwrap());
+
+        let strength = password_data.password_strength("Dog12", &settings);
+        assert_eq!(strength.targeted_entropy, None);
+    }
+
+    #[test]
+    fn test_password_strength_needs_every_bit() {
+        // The generator always emits 8 bits from this data, so "Cat" alone can't come out of it
+        let password_data = PasswordData::new(
+            vec![Number::new(12, NumberType::BirthMonth)],
+            vec!["Cats".to_string()],
+        );
+        let settings = GenerationSettings::builder()
+            .length(8)
+            .symbols(false)
+            .build();
+        assert_eq!(
+            password_data
+                .password_strength("Cat", &settings)
+                .targeted_entropy,
+            None
+        );
+        assert!(password_data
+            .password_strength("CatCatCaC1212Ca12", &settings)
+            .targeted_entropy
+            .is_some());
+    }
+}
@@ -0,0 +1,251 @@
+//! How costly a password is to type on a phone, and a generation mode keeping it cheap
+//!
+//! Phone keyboards only show letters at first. Digits and symbols are on other layers, and every switch between
+//! layers is one more tap, on top of the shift tap before each uppercase letter.
+
+use alloc::vec::Vec;
+
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    password::Password,
+    password_data::PasswordData,
+    password_generation::{GenerationSettings, PasswordGeneration},
+};
+
+/// The default English keyboards of phones
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MobileLayout {
+    #[default]
+    Ios,
+    Android,
+}
+
+/// A layer of a phone keyboard, from the one shown first
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum KeyboardLayer {
+    Letters,
+    /// Reached with the `123` key on iOS and the `?123` key on Android
+    Numbers,
+    /// Reached from the numbers with the `#+=` key on iOS and the `=\<` key on Android
+    Symbols,
+}
+
+impl KeyboardLayer {
+    /// The taps to switch from this layer to `to`. Letters are one tap away from both other layers.
+    fn switch_taps(self, to: KeyboardLayer) -> usize {
+        match (self, to) {
+            (from, to) if from == to => 0,
+            (_, KeyboardLayer::Letters) => 1,
+            (from, to) => (from as usize).abs_diff(to as usize),
+        }
+    }
+}
+
+/// The taps to type a password, including the layer switches
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TypingCost {
+    pub taps: usize,
+    pub layer_switches: usize,
+}
+
+impl MobileLayout {
+    /// The characters on each layer, other than the letters and the space
+    fn layer_keys(&self, layer: KeyboardLayer) -> &'static str {
+        match (self, layer) {
+            (_, KeyboardLayer::Letters) => match self {
+                MobileLayout::Ios => "",
+                MobileLayout::Android => ",.",
+            },
+            (MobileLayout::Ios, KeyboardLayer::Numbers) => "1234567890-/:;()$&@\".,?!'",
+            (MobileLayout::Ios, KeyboardLayer::Symbols) => "[]{}#%^*+=_\\|~<>€£¥•.,?!'",
+            (MobileLayout::Android, KeyboardLayer::Numbers) => "1234567890@#$_&-+()/*\"':;!?,.",
+            (MobileLayout::Android, KeyboardLayer::Symbols) => "~`|•√π÷×¶∆£¢€¥^°={}\\%©®™✓[]<>",
+        }
+    }
+
+    /// The layers a character can be typed on, none for the ones that need a long press
+    pub fn layers_of(&self, c: char) -> Vec<KeyboardLayer> {
+        [
+            KeyboardLayer::Letters,
+            KeyboardLayer::Numbers,
+            KeyboardLayer::Symbols,
+        ]
+        .into_iter()
+        .filter(|&layer| {
+            (layer == KeyboardLayer::Letters && (c.is_ascii_alphabetic() || c == ' '))
+                || self.layer_keys(layer).contains(c)
+        })
+        .collect()
+    }
+
+    /// The settings a mobile password is generated with: only the symbols on the numbers layer, when the symbol set
+    /// has some
+    pub fn mobile_settings(&self, settings: &GenerationSettings) -> GenerationSettings {
+        let mut settings = settings.clone();
+        let reachable: Vec<char> = settings
+            .symbol_set
+            .iter()
+            .copied()
+            .filter(|&c| self.layers_of(c).contains(&KeyboardLayer::Numbers))
+            .collect();
+        if !reachable.is_empty() {
+            settings.symbol_set = reachable;
+        }
+        settings
+    }
+
+    /// The highest layer a bit needs, letters for characters that need a long press
+    fn highest_layer(&self, bits: &str) -> KeyboardLayer {
+        bits.chars()
+            .filter_map(|c| self.layers_of(c).into_iter().min())
+            .max()
+            .unwrap_or(KeyboardLayer::Letters)
+    }
+
+    /// Counts the taps to type a password, starting on the letters. Characters without a key, like accented
+    /// letters, take a long press on the letters, counted as two taps.
+    pub fn typing_cost(&self, password: &str) -> TypingCost {
+        let mut cost = TypingCost::default();
+        let mut layer = KeyboardLayer::Letters;
+        for c in password.chars() {
+            let layers = self.layers_of(c);
+            let target = layers
+                .iter()
+                .copied()
+                .min_by_key(|&to| layer.switch_taps(to))
+                .unwrap_or(KeyboardLayer::Letters);
+            if target != layer {
+                cost.taps += layer.switch_taps(target);
+                cost.layer_switches += 1;
+                layer = target;
+            }
+
+            cost.taps += 1;
+            if c.is_uppercase() {
+                // The shift key
+                cost.taps += 1;
+            }
+            if layers.is_empty() {
+                // The long press
+                cost.taps += 1;
+            }
+        }
+        cost
+    }
+}
+
+pub trait MobileFriendlyGeneration {
+    /// Generates a password that is cheap to type on a phone keyboard
+    ///
+    /// Only the symbols on the numbers layer are used when the symbol set has some (see
+    /// `MobileLayout::mobile_settings`), and the bits are ordered by the
+    /// layer they need: letters, then digits, then symbols. The order of the bits is then not random, so the password
+    /// is weaker than `generation_entropy` tells.
+    #[cfg(feature = "std")]
+    fn generate_mobile_password(
+        &self,
+        settings: &GenerationSettings,
+        layout: MobileLayout,
+    ) -> Password {
+        self.generate_mobile_password_with_rng(settings, layout, &mut rand::thread_rng())
+    }
+
+    /// Generates a password that is cheap to type on a phone keyboard using the given random number generator, for
+    /// targets without `std`
+    fn generate_mobile_password_with_rng<R: Rng + ?Sized>(
+        &self,
+        settings: &GenerationSettings,
+        layout: MobileLayout,
+        rng: &mut R,
+    ) -> Password;
+}
+
+impl MobileFriendlyGeneration for PasswordData {
+    fn generate_mobile_password_with_rng<R: Rng + ?Sized>(
+        &self,
+        settings: &GenerationSettings,
+        layout: MobileLayout,
+        rng: &mut R,
+    ) -> Password {
+        let settings = layout.mobile_settings(settings);
+        let mut bits = self.generate_password_with_rng(&settings, rng).into_bits();
+        // The sort is stable, so bits needing the same layer keep their random order
+        bits.sort_by_key(|bit| layout.highest_layer(&bit.bits));
+        Password::new(bits)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::password_data::{Number, NumberType};
+
+    use super::*;
+
+    #[test]
+    fn test_typing_cost() {
+        let ios = MobileLayout::Ios;
+        assert_eq!(
+            ios.typing_cost("cats"),
+            TypingCost {
+                taps: 4,
+                layer_switches: 0
+            }
+        );
+        // The shift before the C
+        assert_eq!(ios.typing_cost("Cats").taps, 5);
+        // One switch to the numbers
+        assert_eq!(
+            ios.typing_cost("cats05!"),
+            TypingCost {
+                taps: 8,
+                layer_switches: 1
+            }
+        );
+        // To the numbers, back to the letters, then two taps to the symbols
+        assert_eq!(
+            ios.typing_cost("0a#"),
+            TypingCost {
+                taps: 7,
+                layer_switches: 3
+            }
+        );
+        // The Android letters have a comma
+        assert_eq!(ios.typing_cost("a,b").layer_switches, 2);
+        assert_eq!(MobileLayout::Android.typing_cost("a,b").layer_switches, 0);
+        // A long press
+        assert_eq!(ios.typing_cost("é").taps, 2);
+    }
+
+    #[test]
+    fn test_mobile_generation() {
+        let password_data = PasswordData::new(
+            [
+                Number::new(2005, NumberType::BirthYear),
+                Number::new(12, NumberType::BirthMonth),
+            ]
+            .to_vec(),
+            ["Cats", "Lemons", "Apples"].map(String::from).to_vec(),
+        );
+        let settings = GenerationSettings::builder().length(10).build();
+
+        for layout in [MobileLayout::Ios, MobileLayout::Android] {
+            for _ in 0..50 {
+                let password = password_data
+                    .generate_mobile_password(&settings, layout)
+                    .expose_secret();
+                // Letters, then digits and the symbols next to them, so one switch at most
+                assert!(layout.typing_cost(&password).layer_switches <= 1);
+                assert!(password
+                    .chars()
+                    .all(|c| !layout.layers_of(c).contains(&KeyboardLayer::Symbols)
+                        || layout.layers_of(c).contains(&KeyboardLayer::Numbers)));
+            }
+        }
+    }
+}
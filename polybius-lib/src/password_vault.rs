@@ -0,0 +1,222 @@
+//! Passphrase encryption for data kept at rest, like the information the web app saves in the browser
+//!
+//! The key is derived from the passphrase with PBKDF2-HMAC-SHA256 and the data is encrypted with AES-256-GCM. A
+//! sealed text reads `1.600000.<salt>.<nonce>.<ciphertext>` in hex: the version, the PBKDF2 rounds, then what is needed
+//! to open it again.
+
+use std::fmt;
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Key, Nonce,
+};
+use rand::RngCore;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+/// The version of the sealed format
+const VAULT_VERSION: u32 = 1;
+
+/// The PBKDF2 rounds of new vaults, as recommended by OWASP for HMAC-SHA256
+pub const DEFAULT_ROUNDS: u32 = 600_000;
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+/// The reasons a sealed text can't be opened
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum VaultError {
+    /// The sealed text can't be read
+    Malformed,
+    /// The sealed text is of a newer version
+    UnsupportedVersion(u32),
+    /// The passphrase is wrong, or the sealed text was changed
+    WrongPassphrase,
+    /// The sealed text was made with another salt than the one of this vault
+    OtherVault,
+}
+
+impl fmt::Display for VaultError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VaultError::Malformed => write!(f, "The encrypted data can't be read"),
+            VaultError::UnsupportedVersion(version) => {
+                write!(f, "The encryption version {} is not supported", version)
+            }
+            VaultError::WrongPassphrase => write!(f, "The passphrase is wrong"),
+            VaultError::OtherVault => write!(f, "The data was encrypted with another passphrase"),
+        }
+    }
+}
+
+impl std::error::Error for VaultError {}
+
+/// A key derived from a passphrase, to seal and open data without deriving it again each time
+pub struct Vault {
+    key: Zeroizing<[u8; 32]>,
+    rounds: u32,
+    salt: [u8; SALT_LENGTH],
+}
+
+/// The parts of a sealed text
+struct Sealed {
+    rounds: u32,
+    salt: [u8; SALT_LENGTH],
+    nonce: [u8; NONCE_LENGTH],
+    ciphertext: Vec<u8>,
+}
+
+impl Sealed {
+    fn parse(sealed: &str) -> Result<Self, VaultError> {
+        let mut parts = sealed.trim().split('.');
+        let mut next = || parts.next().ok_or(VaultError::Malformed);
+
+        let version: u32 = next()?.parse().map_err(|_| VaultError::Malformed)?;
+        if version != VAULT_VERSION {
+            return Err(VaultError::UnsupportedVersion(version));
+        }
+        let rounds = next()?.parse().map_err(|_| VaultError::Malformed)?;
+        let mut salt = [0; SALT_LENGTH];
+        hex::decode_to_slice(next()?, &mut salt).map_err(|_| VaultError::Malformed)?;
+        let mut nonce = [0; NONCE_LENGTH];
+        hex::decode_to_slice(next()?, &mut nonce).map_err(|_| VaultError::Malformed)?;
+        let ciphertext = hex::decode(next()?).map_err(|_| VaultError::Malformed)?;
+        if parts.next().is_some() {
+            return Err(VaultError::Malformed);
+        }
+
+        Ok(Self {
+            rounds,
+            salt,
+            nonce,
+            ciphertext,
+        })
+    }
+}
+
+impl Vault {
+    /// Derives a key from the passphrase with a new random salt
+    pub fn new(passphrase: &str) -> Self {
+        Self::with_rounds(passphrase, DEFAULT_ROUNDS)
+    }
+
+    /// Derives a key from the passphrase with a new random salt and the given PBKDF2 rounds
+    pub fn with_rounds(passphrase: &str, rounds: u32) -> Self {
+        let mut salt = [0; SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self::derive(passphrase, rounds, salt)
+    }
+
+    /// Derives the key a sealed text was made with, checking the passphrase opens it
+    pub fn unlock(passphrase: &str, sealed: &str) -> Result<Self, VaultError> {
+        let parsed = Sealed::parse(sealed)?;
+        let vault = Self::derive(passphrase, parsed.rounds, parsed.salt);
+        vault.decrypt(&parsed)?;
+        Ok(vault)
+    }
+
+    fn derive(passphrase: &str, rounds: u32, salt: [u8; SALT_LENGTH]) -> Self {
+        let mut key = Zeroizing::new([0; 32]);
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), &salt, rounds, key.as_mut());
+        Self { key, rounds, salt }
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(self.key.as_ref()))
+    }
+
+    /// Encrypts data with a new random nonce
+    pub fn seal(&self, plaintext: &[u8]) -> String {
+        let mut nonce = [0; NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .expect("AES-GCM encrypts any data that fits in memory");
+
+        format!(
+            "{}.{}.{}.{}.{}",
+            VAULT_VERSION,
+            self.rounds,
+            hex::encode(self.salt),
+            hex::encode(nonce),
+            hex::encode(ciphertext)
+        )
+    }
+
+    /// Decrypts a text sealed by this vault, or by one unlocked with the same passphrase
+    pub fn open(&self, sealed: &str) -> Result<Zeroizing<Vec<u8>>, VaultError> {
+        let parsed = Sealed::parse(sealed)?;
+        if parsed.salt != self.salt || parsed.rounds != self.rounds {
+            return Err(VaultError::OtherVault);
+        }
+        self.decrypt(&parsed)
+    }
+
+    fn decrypt(&self, sealed: &Sealed) -> Result<Zeroizing<Vec<u8>>, VaultError> {
+        self.cipher()
+            .decrypt(Nonce::from_slice(&sealed.nonce), sealed.ciphertext.as_ref())
+            .map(Zeroizing::new)
+            .map_err(|_| VaultError::WrongPassphrase)
+    }
+}
+
+impl fmt::Debug for Vault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Vault(<key hidden>)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fewer rounds than the default, to keep the tests fast
+    const ROUNDS: u32 = 1000;
+
+    #[test]
+    fn test_seal_and_open() {
+        let vault = Vault::with_rounds("correct horse", ROUNDS);
+        let sealed = vault.seal(b"Cats, Lemons");
+        assert!(sealed.starts_with("1.1000."));
+        assert!(!sealed.contains("Cats"));
+        // A new nonce each time
+        assert_ne!(vault.seal(b"Cats, Lemons"), sealed);
+        assert_eq!(vault.open(&sealed).unwrap().as_slice(), b"Cats, Lemons");
+
+        let unlocked = Vault::unlock("correct horse", &sealed).unwrap();
+        assert_eq!(
+            unlocked.open(&vault.seal(b"Apples")).unwrap().as_slice(),
+            b"Apples"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let vault = Vault::with_rounds("correct horse", ROUNDS);
+        let sealed = vault.seal(b"Cats");
+        assert_eq!(
+            Vault::unlock("battery staple", &sealed).unwrap_err(),
+            VaultError::WrongPassphrase
+        );
+        assert_eq!(
+            Vault::with_rounds("correct horse", ROUNDS)
+                .open(&sealed)
+                .unwrap_err(),
+            VaultError::OtherVault
+        );
+
+        // Changing a single character of the ciphertext is noticed
+        let mut tampered = sealed.clone();
+        let last = if tampered.ends_with('0') { "1" } else { "0" };
+        tampered.replace_range(tampered.len() - 1.., last);
+        assert_eq!(vault.open(&tampered), Err(VaultError::WrongPassphrase));
+
+        assert_eq!(vault.open("1.1000.zz"), Err(VaultError::Malformed));
+        assert_eq!(
+            vault.open(&sealed.replacen('1', "2", 1)),
+            Err(VaultError::UnsupportedVersion(2))
+        );
+    }
+}
@@ -1,3 +1,27 @@
+pub use crate::password;
 pub use crate::password_bits;
+#[cfg(feature = "std")]
+pub use crate::password_breach;
+pub use crate::password_composition;
 pub use crate::password_data;
+#[cfg(feature = "std")]
+pub use crate::password_export;
 pub use crate::password_generation;
+#[cfg(feature = "std")]
+pub use crate::password_hints;
+pub use crate::password_layouts;
+pub use crate::password_practice;
+pub use crate::password_presets;
+#[cfg(feature = "profile")]
+pub use crate::password_profile;
+pub use crate::password_recipe;
+#[cfg(feature = "std")]
+pub use crate::password_recovery;
+pub use crate::password_rotation;
+pub use crate::password_rules;
+pub use crate::password_segmentation;
+#[cfg(feature = "std")]
+pub use crate::password_strength;
+pub use crate::password_typing;
+#[cfg(feature = "vault")]
+pub use crate::password_vault;
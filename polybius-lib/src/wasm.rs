@@ -0,0 +1,189 @@
+//! JavaScript bindings, built with `wasm-pack build --features wasm`
+//!
+//! The exported names follow JavaScript conventions, so `PasswordData::add_number` is `PasswordData.addNumber`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    password::Password,
+    password_bits::PasswordBit,
+    password_data::{Number, NumberType, PasswordData},
+    password_generation::{GenerationSettings, PasswordGeneration},
+    password_segmentation::{PasswordSegmentation, Segmentation},
+};
+
+/// The data the passwords are generated from
+#[wasm_bindgen(js_name = PasswordData)]
+#[derive(Default)]
+pub struct JsPasswordData {
+    inner: PasswordData,
+}
+
+#[wasm_bindgen(js_class = PasswordData)]
+impl JsPasswordData {
+    /// Creates an empty PasswordData
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a number to the numbers poll
+    #[wasm_bindgen(js_name = addNumber)]
+    pub fn add_number(&mut self, value: u16, num_type: NumberType) {
+        self.inner.numbers_poll.push(Number::new(value, num_type));
+    }
+
+    /// Adds a text to the text poll
+    #[wasm_bindgen(js_name = addText)]
+    pub fn add_text(&mut self, text: String) {
+        self.inner.text_poll.push(text);
+    }
+
+    /// The number of entries in the numbers poll
+    #[wasm_bindgen(getter, js_name = numbersCount)]
+    pub fn numbers_count(&self) -> usize {
+        self.inner.numbers_poll.len()
+    }
+
+    /// The number of entries in the text poll
+    #[wasm_bindgen(getter, js_name = textsCount)]
+    pub fn texts_count(&self) -> usize {
+        self.inner.text_poll.len()
+    }
+}
+
+/// The settings of the password generation
+#[wasm_bindgen(js_name = GenerationSettings)]
+pub struct JsGenerationSettings {
+    inner: GenerationSettings,
+}
+
+#[wasm_bindgen(js_class = GenerationSettings)]
+impl JsGenerationSettings {
+    /// Creates new settings from the number of bits and whether symbols are used
+    #[wasm_bindgen(constructor)]
+    pub fn new(length: usize, symbols: bool) -> Self {
+        Self {
+            inner: GenerationSettings::builder()
+                .length(length)
+                .symbols(symbols)
+                .build(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.inner.length
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_length(&mut self, length: usize) {
+        self.inner.length = length;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn symbols(&self) -> bool {
+        self.inner.symbols
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_symbols(&mut self, symbols: bool) {
+        self.inner.symbols = symbols;
+    }
+}
+
+/// A bit of a password and its importance to the user
+#[wasm_bindgen(js_name = PasswordBit)]
+pub struct JsPasswordBit {
+    inner: PasswordBit,
+}
+
+#[wasm_bindgen(js_class = PasswordBit)]
+impl JsPasswordBit {
+    #[wasm_bindgen(getter)]
+    pub fn bits(&self) -> String {
+        self.inner.bits.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn importance(&self) -> String {
+        self.inner.importance.clone()
+    }
+}
+
+/// A password along with the bits it is made of
+#[wasm_bindgen(js_name = Password)]
+pub struct JsPassword {
+    inner: Password,
+    probability: Option<f64>,
+}
+
+#[wasm_bindgen(js_class = Password)]
+impl JsPassword {
+    /// The password itself
+    #[wasm_bindgen(getter)]
+    pub fn password(&self) -> String {
+        self.inner.to_string()
+    }
+
+    /// The number of characters of the password
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// What each bit stands for, separated by spaces
+    #[wasm_bindgen(getter)]
+    pub fn explanation(&self) -> String {
+        self.inner.explanation()
+    }
+
+    /// The bits of the password, in order
+    #[wasm_bindgen(getter)]
+    pub fn bits(&self) -> Vec<JsPasswordBit> {
+        self.inner
+            .bits()
+            .iter()
+            .map(|bit| JsPasswordBit { inner: bit.clone() })
+            .collect()
+    }
+
+    /// The probability of the generator producing these bits, only set for explanations
+    #[wasm_bindgen(getter)]
+    pub fn probability(&self) -> Option<f64> {
+        self.probability
+    }
+}
+
+impl From<Segmentation> for JsPassword {
+    fn from(segmentation: Segmentation) -> Self {
+        Self {
+            inner: Password::new(segmentation.bits),
+            probability: Some(segmentation.probability),
+        }
+    }
+}
+
+/// Generates a password from the data
+#[wasm_bindgen(js_name = generatePassword)]
+pub fn generate_password(data: &JsPasswordData, settings: &JsGenerationSettings) -> JsPassword {
+    JsPassword {
+        inner: data.inner.generate_password(&settings.inner),
+        probability: None,
+    }
+}
+
+/// Splits a password back into the bits the generator could have produced it from, the most likely first
+/// Throws when the password splits in too many ways.
+#[wasm_bindgen(js_name = explainPassword)]
+pub fn explain_password(
+    data: &JsPasswordData,
+    password: &str,
+    settings: &JsGenerationSettings,
+) -> Result<Vec<JsPassword>, JsError> {
+    let segmentations = data
+        .inner
+        .segment_password(password, &settings.inner)
+        .map_err(|error| JsError::new(&error.to_string()))?;
+    Ok(segmentations.into_iter().map(JsPassword::from).collect())
+}
@@ -0,0 +1,59 @@
+//! Run with `wasm-pack test --node -- --features wasm`
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use polybius_lib::{
+    password_data::NumberType,
+    wasm::{explain_password, generate_password, JsGenerationSettings, JsPasswordData},
+};
+use wasm_bindgen_test::*;
+
+fn example_data() -> JsPasswordData {
+    let mut data = JsPasswordData::new();
+    data.add_number(2005, NumberType::BirthYear);
+    data.add_number(12, NumberType::BirthMonth);
+    data.add_text("Cats".to_string());
+    data.add_text("Lemons".to_string());
+    data
+}
+
+#[wasm_bindgen_test]
+fn test_generate_password() {
+    let data = example_data();
+    let settings = JsGenerationSettings::new(6, true);
+    let password = generate_password(&data, &settings);
+
+    let bits = password.bits();
+    assert_eq!(bits.len(), 6);
+    assert_eq!(
+        password.password(),
+        bits.iter().map(|bit| bit.bits()).collect::<String>()
+    );
+    assert_eq!(password.probability(), None);
+}
+
+#[wasm_bindgen_test]
+fn test_explain_password() {
+    let data = example_data();
+    let settings = JsGenerationSettings::new(3, false);
+    let Ok(explanations) = explain_password(&data, "05CatLe", &settings) else {
+        panic!("The password splits in a few ways");
+    };
+
+    assert_eq!(explanations.len(), 1);
+    let importances: Vec<String> = explanations[0]
+        .bits()
+        .iter()
+        .map(|bit| bit.importance())
+        .collect();
+    assert_eq!(importances, vec!["Birth Year", "Cats", "Lemons"]);
+    assert!(explanations[0].probability().unwrap() > 0.0);
+}
+
+#[wasm_bindgen_test]
+fn test_settings_setters() {
+    let mut settings = JsGenerationSettings::new(8, true);
+    settings.set_length(4);
+    settings.set_symbols(false);
+    assert_eq!(settings.length(), 4);
+    assert!(!settings.symbols());
+}
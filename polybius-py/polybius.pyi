@@ -0,0 +1,59 @@
+"""A smart password generator"""
+
+from typing import List, Optional
+
+class NumberType:
+    BirthYear: NumberType
+    BirthMonth: NumberType
+    BirthDay: NumberType
+    CurrentYear: NumberType
+    RelevantNumber: NumberType
+    def __int__(self) -> int: ...
+
+class Number:
+    value: int
+    num_type: NumberType
+    def __init__(self, value: int, num_type: NumberType = NumberType.RelevantNumber) -> None: ...
+
+class PasswordBit:
+    @property
+    def bits(self) -> str: ...
+    @property
+    def importance(self) -> str: ...
+
+class Password:
+    @property
+    def password(self) -> str: ...
+    @property
+    def bits(self) -> List[PasswordBit]: ...
+    @property
+    def probability(self) -> Optional[float]:
+        """The probability of the generator producing these bits, only set for explanations"""
+    def __len__(self) -> int: ...
+
+class GenerationSettings:
+    length: int
+    symbols: bool
+    def __init__(self, length: int = 8, symbols: bool = True) -> None: ...
+
+class PasswordData:
+    def __init__(self, numbers: List[Number] = [], texts: List[str] = []) -> None: ...
+    @property
+    def numbers(self) -> List[Number]: ...
+    @property
+    def texts(self) -> List[str]: ...
+    def add_number(self, value: int, num_type: NumberType = NumberType.RelevantNumber) -> None:
+        """Adds a number to the numbers poll"""
+    def add_text(self, text: str) -> None:
+        """Adds a text to the text poll"""
+    def generate(self, settings: GenerationSettings) -> Password:
+        """Generates a password"""
+    def generate_many(self, settings: GenerationSettings, count: int) -> List[Password]:
+        """Generates `count` passwords, to study their distribution"""
+    def explain(self, password: str, settings: GenerationSettings) -> List[Password]:
+        """Splits a password back into the bits the generator could have produced it from, the most likely first
+
+        Raises ValueError when the password splits in too many ways."""
+
+def generate_password(data: PasswordData, settings: GenerationSettings) -> Password:
+    """Generates a password from the data"""
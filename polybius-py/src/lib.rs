@@ -0,0 +1,313 @@
+//! Python bindings, built with `maturin develop`
+//!
+//! The typed stubs of this module are in `polybius.pyi`, keep them in sync.
+
+// The wrappers pyo3 0.22 generates for methods returning a PyResult convert the PyErr into itself
+#![allow(clippy::useless_conversion)]
+
+use polybius_lib::{
+    password::Password,
+    password_data::{Number, NumberType, PasswordData},
+    password_generation::{GenerationSettings, PasswordGeneration},
+    password_segmentation::PasswordSegmentation,
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// The significance of a number to the user
+#[pyclass(name = "NumberType", eq, eq_int, module = "polybius")]
+#[derive(Clone, Copy, PartialEq)]
+pub enum PyNumberType {
+    BirthYear,
+    BirthMonth,
+    BirthDay,
+    CurrentYear,
+    RelevantNumber,
+}
+
+#[pymethods]
+impl PyNumberType {
+    fn __str__(&self) -> String {
+        NumberType::from(*self).to_string()
+    }
+}
+
+impl From<PyNumberType> for NumberType {
+    fn from(num_type: PyNumberType) -> Self {
+        match num_type {
+            PyNumberType::BirthYear => NumberType::BirthYear,
+            PyNumberType::BirthMonth => NumberType::BirthMonth,
+            PyNumberType::BirthDay => NumberType::BirthDay,
+            PyNumberType::CurrentYear => NumberType::CurrentYear,
+            PyNumberType::RelevantNumber => NumberType::RelevantNumber,
+        }
+    }
+}
+
+impl From<NumberType> for PyNumberType {
+    fn from(num_type: NumberType) -> Self {
+        match num_type {
+            NumberType::BirthYear => PyNumberType::BirthYear,
+            NumberType::BirthMonth => PyNumberType::BirthMonth,
+            NumberType::BirthDay => PyNumberType::BirthDay,
+            NumberType::CurrentYear => PyNumberType::CurrentYear,
+            NumberType::RelevantNumber => PyNumberType::RelevantNumber,
+        }
+    }
+}
+
+/// A numeric value along with its type
+#[pyclass(name = "Number", eq, module = "polybius")]
+#[derive(Clone, PartialEq)]
+pub struct PyNumber {
+    #[pyo3(get, set)]
+    pub value: u16,
+    #[pyo3(get, set)]
+    pub num_type: PyNumberType,
+}
+
+#[pymethods]
+impl PyNumber {
+    #[new]
+    #[pyo3(signature = (value, num_type = PyNumberType::RelevantNumber))]
+    fn new(value: u16, num_type: PyNumberType) -> Self {
+        Self { value, num_type }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Number({}, NumberType.{:?})",
+            self.value,
+            NumberType::from(self.num_type)
+        )
+    }
+}
+
+impl From<&PyNumber> for Number {
+    fn from(number: &PyNumber) -> Self {
+        Number::new(number.value, number.num_type.into())
+    }
+}
+
+impl From<&Number> for PyNumber {
+    fn from(number: &Number) -> Self {
+        PyNumber {
+            value: number.value,
+            num_type: number.num_type.into(),
+        }
+    }
+}
+
+/// All the information the passwords are generated from
+#[pyclass(name = "PasswordData", module = "polybius")]
+pub struct PyPasswordData {
+    inner: PasswordData,
+}
+
+#[pymethods]
+impl PyPasswordData {
+    #[new]
+    #[pyo3(signature = (numbers = Vec::new(), texts = Vec::new()))]
+    fn new(numbers: Vec<PyNumber>, texts: Vec<String>) -> Self {
+        Self {
+            inner: PasswordData::new(numbers.iter().map(Number::from).collect(), texts),
+        }
+    }
+
+    /// Adds a number to the numbers poll
+    #[pyo3(signature = (value, num_type = PyNumberType::RelevantNumber))]
+    fn add_number(&mut self, value: u16, num_type: PyNumberType) {
+        self.inner
+            .numbers_poll
+            .push(Number::new(value, num_type.into()));
+    }
+
+    /// Adds a text to the text poll
+    fn add_text(&mut self, text: String) {
+        self.inner.text_poll.push(text);
+    }
+
+    #[getter]
+    fn numbers(&self) -> Vec<PyNumber> {
+        self.inner.numbers_poll.iter().map(PyNumber::from).collect()
+    }
+
+    #[getter]
+    fn texts(&self) -> Vec<String> {
+        self.inner.text_poll.clone()
+    }
+
+    /// Generates a password
+    fn generate(&self, settings: &PyGenerationSettings) -> PyPassword {
+        PyPassword::new(self.inner.generate_password(&settings.inner), None)
+    }
+
+    /// Generates `count` passwords, to study their distribution
+    fn generate_many(&self, settings: &PyGenerationSettings, count: usize) -> Vec<PyPassword> {
+        (0..count).map(|_| self.generate(settings)).collect()
+    }
+
+    /// Splits a password back into the bits the generator could have produced it from, the most likely first
+    fn explain(
+        &self,
+        password: &str,
+        settings: &PyGenerationSettings,
+    ) -> PyResult<Vec<PyPassword>> {
+        let segmentations = self
+            .inner
+            .segment_password(password, &settings.inner)
+            .map_err(|error| PyValueError::new_err(error.to_string()))?;
+        Ok(segmentations
+            .into_iter()
+            .map(|segmentation| {
+                PyPassword::new(
+                    Password::new(segmentation.bits),
+                    Some(segmentation.probability),
+                )
+            })
+            .collect())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PasswordData({} numbers, {} texts)",
+            self.inner.numbers_poll.len(),
+            self.inner.text_poll.len()
+        )
+    }
+}
+
+/// The settings of the password generation
+#[pyclass(name = "GenerationSettings", module = "polybius")]
+#[derive(Clone)]
+pub struct PyGenerationSettings {
+    inner: GenerationSettings,
+}
+
+#[pymethods]
+impl PyGenerationSettings {
+    #[new]
+    #[pyo3(signature = (length = 8, symbols = true))]
+    fn new(length: usize, symbols: bool) -> PyResult<Self> {
+        if length == 0 {
+            return Err(PyValueError::new_err("length must be at least 1"));
+        }
+        Ok(Self {
+            inner: GenerationSettings::builder()
+                .length(length)
+                .symbols(symbols)
+                .build(),
+        })
+    }
+
+    #[getter]
+    fn length(&self) -> usize {
+        self.inner.length
+    }
+
+    #[setter]
+    fn set_length(&mut self, length: usize) -> PyResult<()> {
+        if length == 0 {
+            return Err(PyValueError::new_err("length must be at least 1"));
+        }
+        self.inner.length = length;
+        Ok(())
+    }
+
+    #[getter]
+    fn symbols(&self) -> bool {
+        self.inner.symbols
+    }
+
+    #[setter]
+    fn set_symbols(&mut self, symbols: bool) {
+        self.inner.symbols = symbols;
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GenerationSettings(length={}, symbols={})",
+            self.inner.length,
+            if self.inner.symbols { "True" } else { "False" }
+        )
+    }
+}
+
+/// A bit of a password and its importance to the user
+#[pyclass(name = "PasswordBit", frozen, module = "polybius")]
+#[derive(Clone)]
+pub struct PyPasswordBit {
+    #[pyo3(get)]
+    bits: String,
+    #[pyo3(get)]
+    importance: String,
+}
+
+#[pymethods]
+impl PyPasswordBit {
+    fn __repr__(&self) -> String {
+        format!("PasswordBit({:?}, {:?})", self.bits, self.importance)
+    }
+}
+
+/// A password along with the bits it is made of
+#[pyclass(name = "Password", frozen, module = "polybius")]
+pub struct PyPassword {
+    #[pyo3(get)]
+    password: String,
+    #[pyo3(get)]
+    bits: Vec<PyPasswordBit>,
+    /// The probability of the generator producing these bits, only set for explanations
+    #[pyo3(get)]
+    probability: Option<f64>,
+}
+
+impl PyPassword {
+    fn new(password: Password, probability: Option<f64>) -> Self {
+        Self {
+            password: password.to_string(),
+            bits: password
+                .into_bits()
+                .into_iter()
+                .map(|bit| PyPasswordBit {
+                    bits: bit.bits,
+                    importance: bit.importance,
+                })
+                .collect(),
+            probability,
+        }
+    }
+}
+
+#[pymethods]
+impl PyPassword {
+    fn __str__(&self) -> String {
+        self.password.clone()
+    }
+
+    fn __len__(&self) -> usize {
+        self.password.chars().count()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Password({:?})", self.password)
+    }
+}
+
+/// Generates a password from the data
+#[pyfunction]
+fn generate_password(data: &PyPasswordData, settings: &PyGenerationSettings) -> PyPassword {
+    data.generate(settings)
+}
+
+/// A smart password generator
+#[pymodule]
+fn polybius(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyNumberType>()?;
+    module.add_class::<PyNumber>()?;
+    module.add_class::<PyPasswordData>()?;
+    module.add_class::<PyGenerationSettings>()?;
+    module.add_class::<PyPasswordBit>()?;
+    module.add_class::<PyPassword>()?;
+    module.add_function(wrap_pyfunction!(generate_password, module)?)?;
+    Ok(())
+}
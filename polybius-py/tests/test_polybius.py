@@ -0,0 +1,67 @@
+# Build the module with `maturin develop` first, then run `pytest`
+import pytest
+
+from polybius import (
+    GenerationSettings,
+    Number,
+    NumberType,
+    PasswordData,
+    generate_password,
+)
+
+
+@pytest.fixture
+def data():
+    return PasswordData(
+        [Number(2005, NumberType.BirthYear), Number(12, NumberType.BirthMonth)],
+        ["Cats", "Lemons"],
+    )
+
+
+def test_password_data(data):
+    data.add_number(7)
+    data.add_text("Apples")
+    assert data.numbers[-1] == Number(7, NumberType.RelevantNumber)
+    assert data.texts == ["Cats", "Lemons", "Apples"]
+
+
+def test_generate_password(data):
+    settings = GenerationSettings(6, symbols=False)
+    password = generate_password(data, settings)
+    assert len(password.bits) == 6
+    assert password.password == "".join(bit.bits for bit in password.bits)
+    assert len(password) == len(password.password)
+    assert password.probability is None
+    for bit in password.bits:
+        assert bit.importance in ("Birth Year", "Birth Month", "Cats", "Lemons")
+
+
+def test_generate_many(data):
+    passwords = data.generate_many(GenerationSettings(), 100)
+    assert len(passwords) == 100
+    assert all(len(password.bits) == 8 for password in passwords)
+
+
+def test_explain(data):
+    explanations = data.explain("05CatLe", GenerationSettings(3, symbols=False))
+    assert len(explanations) == 1
+    assert [bit.importance for bit in explanations[0].bits] == [
+        "Birth Year",
+        "Cats",
+        "Lemons",
+    ]
+    assert 0 < explanations[0].probability < 1
+
+
+def test_settings_validation():
+    settings = GenerationSettings()
+    assert settings.length == 8
+    assert settings.symbols
+    with pytest.raises(ValueError):
+        GenerationSettings(0)
+    with pytest.raises(ValueError):
+        settings.length = 0
+
+
+def test_number_type_str():
+    assert str(NumberType.BirthDay) == "Birth Day"
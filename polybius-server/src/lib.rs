@@ -0,0 +1,59 @@
+pub mod routes;
+pub mod schema;
+
+use std::io::Read;
+
+use polybius_lib::password_breach::BreachCorpus;
+use tiny_http::{Header, Request, Response, Server};
+
+/// Requests with a bigger body are refused
+pub const MAX_BODY_SIZE: u64 = 64 * 1024;
+
+/// Everything the endpoints share between requests
+#[derive(Default)]
+pub struct ServerState {
+    /// The corpus `/audit` checks passwords against, if any
+    pub breach_corpus: Option<Box<dyn BreachCorpus + Send + Sync>>,
+}
+
+/// Answers the requests of the server, one at a time, until it shuts down
+pub fn serve(server: &Server, state: &ServerState) {
+    for request in server.incoming_requests() {
+        handle_request(request, state);
+    }
+}
+
+fn handle_request(mut request: Request, state: &ServerState) {
+    let method = request.method().to_string();
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    let mut body = String::new();
+    let (status, response) = match request
+        .as_reader()
+        .take(MAX_BODY_SIZE + 1)
+        .read_to_string(&mut body)
+    {
+        Ok(read) if read as u64 > MAX_BODY_SIZE => {
+            routes::error(413, "The request body is too large")
+        }
+        Ok(_) => routes::route(state, &method, &path, &body),
+        Err(_) => routes::error(400, "The request body is not valid UTF-8"),
+    };
+
+    // Only the request line is logged, the body holds personal data
+    eprintln!("{} {} {}", method, path, status);
+
+    let content_type = Header::from_bytes("Content-Type", "application/json")
+        .expect("The Content-Type header is valid");
+    let response = Response::from_string(response.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(error) = request.respond(response) {
+        eprintln!("Failed to respond to {} {}: {}", method, path, error);
+    }
+}
@@ -0,0 +1,97 @@
+use std::{fs::File, io::BufReader, net::SocketAddr, process::ExitCode};
+
+use anyhow::{bail, Context};
+use polybius_lib::password_breach::{BloomFilter, BreachCorpus, SortedHashFile, Wordlist};
+use polybius_server::{serve, ServerState};
+use tiny_http::Server;
+
+const USAGE: &str = "Usage: polybius-server [OPTIONS]
+
+Options:
+    --bind <ADDRESS>          The address to listen on (default 127.0.0.1:7878)
+    --allow-remote            Allow listening on an address other than the loopback
+    --breach-wordlist <PATH>  Check /audit passwords against a wordlist, one password per line
+    --breach-hashes <PATH>    Check /audit passwords against a sorted SHA1:COUNT file
+    --breach-bloom <PATH>     Check /audit passwords against a Polybius Bloom filter
+    --help                    Print this message";
+
+/// The options given on the command line
+struct Options {
+    bind: SocketAddr,
+    allow_remote: bool,
+    breach_corpus: Option<Box<dyn BreachCorpus + Send + Sync>>,
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> anyhow::Result<Option<Options>> {
+    let mut options = Options {
+        bind: SocketAddr::from(([127, 0, 0, 1], 7878)),
+        allow_remote: false,
+        breach_corpus: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .with_context(|| format!("{} needs a value", arg))
+        };
+        match arg.as_str() {
+            "--bind" => {
+                options.bind = value()?
+                    .parse()
+                    .context("--bind needs an IP:PORT address")?
+            }
+            "--allow-remote" => options.allow_remote = true,
+            "--breach-wordlist" => {
+                let file = File::open(value()?).context("Failed to open the wordlist")?;
+                options.breach_corpus = Some(Box::new(Wordlist::from_reader(BufReader::new(file))?))
+            }
+            "--breach-hashes" => {
+                options.breach_corpus = Some(Box::new(SortedHashFile::open(value()?)?))
+            }
+            "--breach-bloom" => {
+                let file = File::open(value()?).context("Failed to open the Bloom filter")?;
+                options.breach_corpus =
+                    Some(Box::new(BloomFilter::read_from(BufReader::new(file))?))
+            }
+            "--help" | "-h" => return Ok(None),
+            _ => bail!("Unknown option {}\n\n{}", arg, USAGE),
+        }
+    }
+
+    if !options.bind.ip().is_loopback() && !options.allow_remote {
+        bail!(
+            "{} is not a loopback address, pass --allow-remote to listen on it anyway",
+            options.bind
+        );
+    }
+    Ok(Some(options))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("{:#}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let server = match Server::http(options.bind) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("Failed to listen on {}: {}", options.bind, error);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("Listening on http://{}", options.bind);
+
+    let state = ServerState {
+        breach_corpus: options.breach_corpus,
+    };
+    serve(&server, &state);
+    ExitCode::SUCCESS
+}
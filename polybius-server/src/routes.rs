@@ -0,0 +1,196 @@
+use polybius_lib::{
+    password_generation::{GenerationSettings, PasswordGeneration},
+    password_segmentation::PasswordSegmentation,
+    password_strength::PasswordStrength,
+    password_typing::MobileFriendlyGeneration,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    schema::{
+        AuditRequest, AuditResponse, EntropyRequest, EntropyResponse, ErrorResponse,
+        ExplainRequest, ExplainResponse, GenerateRequest, GenerateResponse, GeneratedPassword,
+    },
+    ServerState,
+};
+
+/// The most passwords a single `/generate` request can ask for
+pub const MAX_COUNT: usize = 100;
+
+/// The most bits a generated password can have
+pub const MAX_LENGTH: usize = 64;
+
+/// The longest password `/explain` and `/audit` accept, in characters
+pub const MAX_PASSWORD_LENGTH: usize = 64;
+
+/// Passwords shorter than this are flagged by `/audit`
+const MIN_AUDIT_LENGTH: usize = 8;
+
+/// Passwords easier to guess than this for someone who knows the data are flagged by `/audit`
+const MIN_AUDIT_TARGETED_ENTROPY: f64 = 28.0;
+
+/// Returns the status code and the JSON body answering a request
+pub fn route(state: &ServerState, method: &str, path: &str, body: &str) -> (u16, Value) {
+    match (method, path) {
+        ("GET", "/health") => (200, json!({ "status": "ok" })),
+        ("POST", "/generate") => respond(body, generate),
+        ("POST", "/explain") => respond(body, explain),
+        ("POST", "/entropy") => respond(body, entropy),
+        ("POST", "/audit") => respond(body, |request| audit(state, request)),
+        (_, "/health" | "/generate" | "/explain" | "/entropy" | "/audit") => {
+            error(405, "Method not allowed")
+        }
+        _ => error(404, "Not found"),
+    }
+}
+
+/// Returns the status code and the JSON body of an error
+pub fn error(status: u16, message: &str) -> (u16, Value) {
+    let body = ErrorResponse {
+        error: message.to_string(),
+    };
+    (status, serde_json::to_value(body).unwrap_or_default())
+}
+
+fn respond<Req: DeserializeOwned, Res: Serialize>(
+    body: &str,
+    endpoint: impl FnOnce(Req) -> Result<Res, String>,
+) -> (u16, Value) {
+    // serde_json errors can quote the values they failed on, so only the position is reported
+    let request = match serde_json::from_str::<Req>(body) {
+        Ok(request) => request,
+        Err(e) => {
+            return error(
+                400,
+                &format!(
+                    "The request body does not match the endpoint schema (line {}, column {})",
+                    e.line(),
+                    e.column()
+                ),
+            )
+        }
+    };
+
+    match endpoint(request).map(serde_json::to_value) {
+        Ok(Ok(response)) => (200, response),
+        Ok(Err(_)) => error(500, "The response could not be serialized"),
+        Err(message) => error(422, &message),
+    }
+}
+
+/// Keeps the work of a single request bounded, along with the cap on segmentations of `/explain`. `/audit` sums the
+/// segmentations in polynomial time, so these limits bound it too.
+fn check_limits(settings: &GenerationSettings, password: Option<&str>) -> Result<(), String> {
+    if settings.length > MAX_LENGTH {
+        return Err(format!("settings.length must be at most {}", MAX_LENGTH));
+    }
+    if password.is_some_and(|password| password.chars().count() > MAX_PASSWORD_LENGTH) {
+        return Err(format!(
+            "password must be at most {} characters long",
+            MAX_PASSWORD_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+fn generate(request: GenerateRequest) -> Result<GenerateResponse, String> {
+    if request.count == 0 || request.count > MAX_COUNT {
+        return Err(format!("count must be between 1 and {}", MAX_COUNT));
+    }
+    check_limits(&request.settings, None)?;
+    request
+        .settings
+        .validate(&request.data)
+        .map_err(|error| error.to_string())?;
+
+    // Mobile passwords are generated with fewer symbols, so their strength is rated against those
+    let settings = match request.mobile_layout {
+        Some(layout) => layout.mobile_settings(&request.settings),
+        None => request.settings.clone(),
+    };
+    let passwords = (0..request.count)
+        .map(|_| {
+            let password = match request.mobile_layout {
+                Some(layout) => request
+                    .data
+                    .generate_mobile_password(&request.settings, layout),
+                None => request.data.generate_password(&request.settings),
+            };
+            let secret = password.expose_secret();
+            GeneratedPassword {
+                password: password.to_string(),
+                strength: request.data.password_strength(&secret, &settings),
+                typing_cost: request
+                    .mobile_layout
+                    .unwrap_or_default()
+                    .typing_cost(&secret),
+                bits: password.into_bits(),
+            }
+        })
+        .collect();
+    Ok(GenerateResponse { passwords })
+}
+
+fn explain(request: ExplainRequest) -> Result<ExplainResponse, String> {
+    check_limits(&request.settings, Some(&request.password))?;
+    Ok(ExplainResponse {
+        segmentations: request
+            .data
+            .segment_password(&request.password, &request.settings)
+            .map_err(|error| error.to_string())?,
+    })
+}
+
+fn entropy(request: EntropyRequest) -> Result<EntropyResponse, String> {
+    check_limits(&request.settings, None)?;
+    Ok(EntropyResponse {
+        generation_entropy: request.data.generation_entropy(&request.settings),
+    })
+}
+
+fn audit(state: &ServerState, request: AuditRequest) -> Result<AuditResponse, String> {
+    check_limits(&request.settings, Some(&request.password))?;
+    let strength = request
+        .data
+        .password_strength(&request.password, &request.settings);
+    let mut warnings = Vec::new();
+    let breached =
+        state
+            .breach_corpus
+            .as_ref()
+            .and_then(|corpus| match corpus.check(&request.password) {
+                Ok(breached) => Some(breached),
+                Err(error) => {
+                    eprintln!("Failed to check the breach corpus: {:#}", error);
+                    warnings.push("The breach corpus could not be checked".to_string());
+                    None
+                }
+            });
+
+    if request.password.chars().count() < MIN_AUDIT_LENGTH {
+        warnings.push(format!(
+            "The password is shorter than {} characters",
+            MIN_AUDIT_LENGTH
+        ));
+    }
+    match strength.targeted_entropy {
+        Some(entropy) if entropy < MIN_AUDIT_TARGETED_ENTROPY => warnings.push(format!(
+            "The password has {:.1} bits of entropy for someone who knows the data",
+            entropy
+        )),
+        Some(_) => {}
+        None => warnings.push("The generator can't produce the password from the data".to_string()),
+    }
+    if breached == Some(true) {
+        warnings.push("The password is in the breach corpus".to_string());
+    }
+
+    Ok(AuditResponse {
+        strength,
+        typing_cost: request.mobile_layout.typing_cost(&request.password),
+        explained: strength.targeted_entropy.is_some(),
+        breached,
+        warnings,
+    })
+}
@@ -0,0 +1,99 @@
+use polybius_lib::{
+    password_bits::PasswordBits,
+    password_data::PasswordData,
+    password_generation::GenerationSettings,
+    password_segmentation::Segmentation,
+    password_strength::Strength,
+    password_typing::{MobileLayout, TypingCost},
+};
+use serde::{Deserialize, Serialize};
+
+fn one() -> usize {
+    1
+}
+
+/// `POST /generate`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenerateRequest {
+    pub data: PasswordData,
+    pub settings: GenerationSettings,
+    /// How many passwords to generate, 1 by default
+    #[serde(default = "one")]
+    pub count: usize,
+    /// Generates passwords cheap to type on this phone keyboard when set
+    pub mobile_layout: Option<MobileLayout>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GeneratedPassword {
+    pub password: String,
+    pub bits: PasswordBits,
+    /// Rated against the symbols the password was generated with. The targeted entropy is an upper bound for mobile
+    /// passwords, as their bits are ordered by layer.
+    pub strength: Strength,
+    /// The taps to type the password on the requested phone keyboard, iOS when there is none
+    pub typing_cost: TypingCost,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GenerateResponse {
+    pub passwords: Vec<GeneratedPassword>,
+}
+
+/// `POST /explain`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplainRequest {
+    pub data: PasswordData,
+    pub settings: GenerationSettings,
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExplainResponse {
+    /// Every way the password splits into bits, the most likely first
+    pub segmentations: Vec<Segmentation>,
+}
+
+/// `POST /entropy`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntropyRequest {
+    pub data: PasswordData,
+    pub settings: GenerationSettings,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EntropyResponse {
+    /// Bits of entropy of the generator output, for an attacker that knows the data
+    pub generation_entropy: f64,
+}
+
+/// `POST /audit`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditRequest {
+    pub data: PasswordData,
+    pub settings: GenerationSettings,
+    pub password: String,
+    /// The phone keyboard to count the typing cost on, iOS by default
+    #[serde(default)]
+    pub mobile_layout: MobileLayout,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuditResponse {
+    pub strength: Strength,
+    pub typing_cost: TypingCost,
+    /// Whether the generator could have produced the password from the data
+    pub explained: bool,
+    /// Whether the password is in the breach corpus. None when the server has no corpus, or couldn't read it.
+    pub breached: Option<bool>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
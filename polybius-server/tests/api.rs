@@ -0,0 +1,221 @@
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream},
+    thread,
+};
+
+use polybius_lib::password_breach::Wordlist;
+use polybius_server::{serve, ServerState};
+use serde_json::{json, Value};
+use tiny_http::Server;
+
+/// Starts a server on an ephemeral loopback port and returns its address
+fn start_server(state: ServerState) -> SocketAddr {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let address = server.server_addr().to_ip().unwrap();
+    thread::spawn(move || serve(&server, &state));
+    address
+}
+
+/// Sends a request and returns the status code and the JSON body of the response
+fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method,
+        path,
+        address,
+        body.len(),
+        body
+    )
+    .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+fn profile() -> Value {
+    json!({
+        "data": {
+            "numbers_poll": [
+                { "value": 2005, "num_type": "birth_year" },
+                { "value": 12, "num_type": "birth_month" }
+            ],
+            "text_poll": ["Cats", "Lemons", "Apples"]
+        },
+        "settings": { "length": 6, "symbols": true }
+    })
+}
+
+#[test]
+fn test_health() {
+    let address = start_server(ServerState::default());
+    let (status, body) = request(address, "GET", "/health", "");
+    assert_eq!(status, 200);
+    assert_eq!(body["status"], "ok");
+}
+
+#[test]
+fn test_generate() {
+    let address = start_server(ServerState::default());
+    let mut body = profile();
+    body["count"] = json!(5);
+    let (status, body) = request(address, "POST", "/generate", &body.to_string());
+    assert_eq!(status, 200);
+
+    let passwords = body["passwords"].as_array().unwrap();
+    assert_eq!(passwords.len(), 5);
+    for password in passwords {
+        let bits = password["bits"].as_array().unwrap();
+        assert_eq!(bits.len(), 6);
+        let joined: String = bits
+            .iter()
+            .map(|bit| bit["bits"].as_str().unwrap())
+            .collect();
+        assert_eq!(password["password"], joined);
+        assert!(password["strength"]["targeted_entropy"].as_f64().unwrap() > 0.0);
+        assert!(password["typing_cost"]["taps"].as_u64().unwrap() >= joined.len() as u64);
+    }
+}
+
+#[test]
+fn test_generate_mobile() {
+    let address = start_server(ServerState::default());
+    let mut body = profile();
+    body["count"] = json!(5);
+    body["mobile_layout"] = json!("android");
+    let (status, body) = request(address, "POST", "/generate", &body.to_string());
+    assert_eq!(status, 200);
+
+    for password in body["passwords"].as_array().unwrap() {
+        assert!(password["typing_cost"]["layer_switches"].as_u64().unwrap() <= 1);
+    }
+}
+
+#[test]
+fn test_generate_mobile_strength() {
+    let address = start_server(ServerState::default());
+    // Only the ! is on the numbers layer of Android
+    let mut body = profile();
+    body["count"] = json!(5);
+    body["mobile_layout"] = json!("android");
+    body["settings"]["symbol_set"] = json!(["!", "~"]);
+    let (status, body) = request(address, "POST", "/generate", &body.to_string());
+    assert_eq!(status, 200);
+
+    for password in body["passwords"].as_array().unwrap() {
+        let mut audit = profile();
+        audit["settings"]["symbol_set"] = json!(["!"]);
+        audit["password"] = password["password"].clone();
+        let (status, audit) = request(address, "POST", "/audit", &audit.to_string());
+        assert_eq!(status, 200);
+        assert_eq!(
+            password["strength"]["targeted_entropy"],
+            audit["strength"]["targeted_entropy"]
+        );
+    }
+}
+
+#[test]
+fn test_explain() {
+    let address = start_server(ServerState::default());
+    let mut body = profile();
+    body["password"] = json!("05CatLe");
+    body["settings"]["length"] = json!(3);
+    let (status, body) = request(address, "POST", "/explain", &body.to_string());
+    assert_eq!(status, 200);
+
+    let segmentations = body["segmentations"].as_array().unwrap();
+    assert_eq!(segmentations.len(), 1);
+    let importances: Vec<&str> = segmentations[0]["bits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bit| bit["importance"].as_str().unwrap())
+        .collect();
+    assert_eq!(importances, vec!["Birth Year", "Cats", "Lemons"]);
+    assert_eq!(segmentations[0]["sources"][0]["kind"], "number");
+}
+
+#[test]
+fn test_entropy() {
+    let address = start_server(ServerState::default());
+    let (status, body) = request(address, "POST", "/entropy", &profile().to_string());
+    assert_eq!(status, 200);
+    assert!(body["generation_entropy"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn test_audit() {
+    let state = ServerState {
+        breach_corpus: Some(Box::new(
+            Wordlist::from_reader("Cat05!\n".as_bytes()).unwrap(),
+        )),
+    };
+    let address = start_server(state);
+
+    let mut body = profile();
+    body["password"] = json!("Cat05!");
+    body["settings"]["length"] = json!(3);
+    let (status, body) = request(address, "POST", "/audit", &body.to_string());
+    assert_eq!(status, 200);
+    assert_eq!(body["explained"], true);
+    assert_eq!(body["breached"], true);
+    assert!(body["strength"]["generic_entropy"].as_f64().unwrap() > 0.0);
+    // Cat, the switch to the numbers, then 05!
+    assert_eq!(body["typing_cost"]["taps"], 8);
+    assert!(!body["warnings"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_repetitive_data_is_bounded() {
+    let address = start_server(ServerState::default());
+    // A run of "a"s splits in exponentially many ways
+    let body = json!({
+        "data": { "numbers_poll": [], "text_poll": ["aaa"] },
+        "settings": { "length": 64, "symbols": false },
+        "password": "a".repeat(64)
+    });
+
+    let (status, response) = request(address, "POST", "/explain", &body.to_string());
+    assert_eq!(status, 422);
+    assert!(response["error"].as_str().unwrap().contains("ways"));
+
+    // The strength sums the segmentations without listing them
+    let (status, response) = request(address, "POST", "/audit", &body.to_string());
+    assert_eq!(status, 200);
+    assert_eq!(response["explained"], true);
+}
+
+#[test]
+fn test_errors_do_not_echo_the_body() {
+    let address = start_server(ServerState::default());
+
+    let body = json!({
+        "data": { "numbers_poll": [], "text_poll": "MySecretPetName" },
+        "settings": { "length": 6, "symbols": true }
+    });
+    let (status, body) = request(address, "POST", "/generate", &body.to_string());
+    assert_eq!(status, 400);
+    assert!(!body["error"].as_str().unwrap().contains("MySecretPetName"));
+
+    let mut body = profile();
+    body["count"] = json!(0);
+    let (status, _) = request(address, "POST", "/generate", &body.to_string());
+    assert_eq!(status, 422);
+
+    let mut body = profile();
+    body["settings"]["length"] = json!(0);
+    let (status, _) = request(address, "POST", "/generate", &body.to_string());
+    assert_eq!(status, 422);
+
+    let (status, _) = request(address, "GET", "/generate", "");
+    assert_eq!(status, 405);
+
+    let (status, _) = request(address, "GET", "/nothing", "");
+    assert_eq!(status, 404);
+}
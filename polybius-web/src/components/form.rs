@@ -1,18 +1,41 @@
+use std::{collections::HashMap, rc::Rc};
+
 use chrono::Datelike;
+use gloo_file::{callbacks::FileReader, Blob, File, ObjectUrl};
 use polybius_lib::{
-    password_bits::PasswordBits,
+    password::Password,
+    password_bits::{PasswordBit, SYMBOLS},
+    password_breach::{BreachAwareGeneration, BreachCorpus, HibpHashes, Wordlist},
+    password_composition::ComposedGeneration,
     password_data::{Number, NumberType, PasswordData},
     password_generation::{GenerationSettings, PasswordGeneration},
+    password_layouts::{restrict_to_layouts, KeyboardLayout},
+    password_practice::PracticeSchedule,
+    password_profile::{Profile, ProfileError},
+    password_recipe::Recipe,
+    password_strength::{PasswordStrength, Strength},
+    password_typing::{MobileLayout, TypingCost},
+    password_vault::Vault,
 };
-use web_sys::{console, HtmlInputElement};
+use web_sys::{wasm_bindgen::JsCast, HtmlAnchorElement, HtmlInputElement};
 use yew::prelude::*;
 
 use crate::{
     components::{
-        input_bits::InputBits, input_numeric::InputNumeric, input_string::InputString,
+        input_bits::InputBits,
+        input_breach_list::InputBreachList,
+        input_layouts::InputLayouts,
+        input_numeric::InputNumeric,
+        input_profile::InputProfile,
+        input_storage::{InputStorage, StorageMode},
+        input_string::InputString,
         list_tile_switch::ListTileSwitch,
+        password_result::{Edit, PasswordResult},
+        trainer::{now, Trainer},
     },
+    storage::{self, Saved, SavedForm},
     traits::data_serialization::DataSerialization,
+    validation::{parse_bits, parse_number, text_error, trimmed_texts},
 };
 
 pub enum Msg {
@@ -20,22 +43,366 @@ pub enum Msg {
     AddStringInput,
     RemoveNumericInput(usize),
     RemoveStringInput(usize),
-    UpdateNumericValueInput(usize, u16),
+    UpdateNumericValueInput(usize, String),
     UpdateNumericTypeInput(usize, NumberType),
     UpdateStringInput(usize, String),
-    UpdatePasswordBits(usize),
+    UpdatePasswordBits(String),
     FlipAddYear,
     FlipAddSymbols,
+    ToggleLayout(KeyboardLayout),
+    LoadBreachList(Option<File>),
+    BreachListLoaded(Result<String, String>),
+    ClearBreachList,
     GeneratePasswords,
+    EditPassword(usize, Edit),
+    Practice(Password),
+    ResumePractice(String),
+    RecordPractice(PracticeSchedule),
+    ClosePractice,
+    Remember(Option<String>),
+    Unlock(String),
+    Forget,
+    ExportProfile(Option<String>),
+    LoadProfile(Option<File>),
+    ProfileLoaded(Result<String, String>),
+    UnlockProfile(String),
 }
 
 pub struct FormComponent {
     pub numeric_values: Vec<Number>,
+    /// The text typed for each numeric value, as the value is only updated when the text is valid
+    pub numeric_inputs: Vec<String>,
     pub string_values: Vec<String>,
     pub add_year: bool,
     pub add_symbols: bool,
+    /// The keyboard layouts the symbols must be typable on without AltGr, any symbol when empty
+    pub keyboard_layouts: Vec<KeyboardLayout>,
     pub password_bits: usize,
-    pub passwords: Option<Vec<PasswordBits>>,
+    /// The symbols a symbol bit can be, from a loaded profile, before the keyboard layouts narrow them
+    pub symbol_set: Vec<char>,
+    /// The text typed for the password bits
+    pub password_bits_input: String,
+    pub passwords: Option<Vec<GeneratedPassword>>,
+    pub breach_list: Option<Rc<dyn BreachCorpus>>,
+    pub breach_status: Option<String>,
+    pub breach_reader: Option<FileReader>,
+    /// The password being practiced, along with its recipe
+    pub practice: Option<(Password, String)>,
+    /// The practice schedules, by recipe, saved with the form
+    pub practice_schedules: HashMap<String, PracticeSchedule>,
+    pub storage_mode: StorageMode,
+    pub storage_status: Option<String>,
+    /// The key the form is saved with, when it is encrypted
+    pub vault: Option<Vault>,
+    /// The saved form, while it waits for the passphrase
+    pub sealed: Option<String>,
+    pub profile_status: Option<String>,
+    pub profile_reader: Option<FileReader>,
+    /// A loaded profile, while it waits for the passphrase
+    pub pending_profile: Option<String>,
+    /// The last downloaded profile, revoked when dropped
+    pub profile_url: Option<ObjectUrl>,
+}
+
+/// A generated password, as edited since
+pub struct GeneratedPassword {
+    pub password: Password,
+    pub strength: Strength,
+    /// The taps to type the password on a phone keyboard
+    pub typing_cost: TypingCost,
+    /// Whether each bit is kept when regenerating
+    pub locked: Vec<bool>,
+    /// Whether bits were picked by hand, so the generator odds the targeted strength relies on don't hold
+    pub edited: bool,
+}
+
+/// How many passwords are generated at a time
+const PASSWORDS_COUNT: usize = 10;
+
+/// How many times a password found in the breach list is generated again before giving up
+const BREACH_ATTEMPTS: usize = 100;
+
+/// The name of downloaded profiles
+const PROFILE_FILE_NAME: &str = "polybius-profile.json";
+
+/// Makes the browser download a file, returning its URL to keep it alive until the download starts
+fn download(name: &str, contents: &str) -> Option<ObjectUrl> {
+    let url = ObjectUrl::from(Blob::new_with_options(contents, Some("application/json")));
+    let link: HtmlAnchorElement = web_sys::window()?
+        .document()?
+        .create_element("a")
+        .ok()?
+        .dyn_into()
+        .ok()?;
+    link.set_href(&url);
+    link.set_download(name);
+    link.click();
+    Some(url)
+}
+
+/// Reads a breach list, either Have I Been Pwned SHA1:COUNT lines or a plain wordlist
+fn read_breach_list(contents: &str) -> Result<(Rc<dyn BreachCorpus>, usize), String> {
+    let is_hibp = contents
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| {
+            let hash = line.split(':').next().unwrap_or_default().trim();
+            hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit())
+        })
+        .unwrap_or(false);
+
+    if is_hibp {
+        let hashes = HibpHashes::from_reader(contents.as_bytes()).map_err(|e| e.to_string())?;
+        let count = hashes.len();
+        Ok((Rc::new(hashes), count))
+    } else {
+        let wordlist = Wordlist::from_reader(contents.as_bytes()).map_err(|e| e.to_string())?;
+        let count = wordlist.len();
+        Ok((Rc::new(wordlist), count))
+    }
+}
+
+impl FormComponent {
+    /// The information of the form that is remembered between visits
+    fn saved_form(&self) -> SavedForm {
+        SavedForm {
+            numeric_values: self.numeric_values.clone(),
+            string_values: self.string_values.clone(),
+            add_year: self.add_year,
+            add_symbols: self.add_symbols,
+            password_bits: self.password_bits,
+            symbol_set: self.symbol_set.clone(),
+            keyboard_layouts: self.keyboard_layouts.clone(),
+            practice: self.practice_schedules.clone(),
+        }
+    }
+
+    /// Loads a profile file into the form, keeping it for the passphrase when it is encrypted
+    fn import_profile(&mut self, contents: String, passphrase: Option<&str>) {
+        match Profile::import(&contents, passphrase) {
+            Ok(profile) => {
+                // Profiles don't carry the practice, so the schedules of this browser are kept
+                let mut saved = SavedForm::from(profile);
+                saved.practice = std::mem::take(&mut self.practice_schedules);
+                self.restore(saved);
+                self.passwords = None;
+                self.practice = None;
+                self.pending_profile = None;
+                self.profile_status = Some("Profile loaded".to_string());
+            }
+            Err(ProfileError::PassphraseNeeded) => {
+                self.pending_profile = Some(contents);
+                self.profile_status =
+                    Some("This profile is encrypted, enter its passphrase".to_string());
+            }
+            Err(error @ ProfileError::Vault(_)) => {
+                self.pending_profile = Some(contents);
+                self.profile_status = Some(error.to_string());
+            }
+            Err(error) => {
+                self.pending_profile = None;
+                self.profile_status = Some(format!("The profile could not be loaded: {}", error));
+            }
+        }
+    }
+
+    fn restore(&mut self, saved: SavedForm) {
+        self.numeric_inputs = saved
+            .numeric_values
+            .iter()
+            .map(|number| number.value.to_string())
+            .collect();
+        self.numeric_values = saved.numeric_values;
+        self.string_values = saved.string_values;
+        self.add_year = saved.add_year;
+        self.add_symbols = saved.add_symbols;
+        self.password_bits = saved.password_bits;
+        self.password_bits_input = saved.password_bits.to_string();
+        self.symbol_set = saved.symbol_set;
+        self.keyboard_layouts = saved.keyboard_layouts;
+        self.practice_schedules = saved.practice;
+    }
+
+    /// The data the passwords are generated from, with the current year when it is added
+    fn password_data(&self) -> PasswordData {
+        let numbers: Vec<Number> = {
+            if self.add_year {
+                let current_year = chrono::Local::now().year();
+                [
+                    vec![Number::new(current_year as u16, NumberType::CurrentYear)],
+                    self.numeric_values.clone(),
+                ]
+                .concat()
+            } else {
+                self.numeric_values.clone()
+            }
+        };
+        PasswordData::new(numbers, trimmed_texts(&self.string_values))
+    }
+
+    /// Why the passwords can't be generated, if the form is invalid
+    fn form_error(&self) -> Option<String> {
+        let numbers_valid = self
+            .numeric_values
+            .iter()
+            .zip(&self.numeric_inputs)
+            .all(|(number, input)| parse_number(input, number.num_type).is_ok());
+        let texts_valid = (0..self.string_values.len())
+            .all(|index| text_error(&self.string_values, index).is_none());
+        if !numbers_valid || !texts_valid || parse_bits(&self.password_bits_input).is_err() {
+            return Some("Fix the highlighted fields to generate passwords".to_string());
+        }
+        self.password_settings()
+            .validate(&self.password_data())
+            .err()
+            .map(|error| error.to_string())
+    }
+
+    /// The settings the passwords are generated with
+    fn password_settings(&self) -> GenerationSettings {
+        restrict_to_layouts(
+            &self.keyboard_layouts,
+            &GenerationSettings::builder()
+                .length(self.password_bits)
+                .symbols(self.add_symbols)
+                .symbol_set(self.symbol_set.iter().copied())
+                .build(),
+        )
+    }
+
+    fn generated_password(
+        &self,
+        password: Password,
+        locked: Vec<bool>,
+        edited: bool,
+    ) -> GeneratedPassword {
+        let secret = password.expose_secret();
+        let mut strength = self
+            .password_data()
+            .password_strength(&secret, &self.password_settings());
+        if edited {
+            strength.targeted_entropy = None;
+        }
+        let typing_cost = MobileLayout::default().typing_cost(&secret);
+        GeneratedPassword {
+            password,
+            strength,
+            typing_cost,
+            locked,
+            edited,
+        }
+    }
+
+    /// The items that can be added to a password by hand: the numbers, the texts, then the symbols
+    fn bit_choices(&self) -> Vec<AttrValue> {
+        let password_data = self.password_data();
+        let numbers = password_data
+            .numbers_poll
+            .iter()
+            .map(|number| format!("{} ({})", number.value, number.num_type));
+        let texts = password_data.text_poll.iter().cloned();
+        let symbols = self
+            .password_settings()
+            .symbol_set
+            .into_iter()
+            .filter(|_| self.add_symbols)
+            .map(|symbol| format!("Symbol {}", symbol));
+        numbers
+            .chain(texts)
+            .chain(symbols)
+            .map(AttrValue::from)
+            .collect()
+    }
+
+    /// The bit of one of `bit_choices`
+    fn chosen_bit(&self, choice: usize) -> Option<PasswordBit> {
+        let password_data = self.password_data();
+        let numbers = password_data.numbers_poll.len();
+        let texts = password_data.text_poll.len();
+        if let Some(number) = password_data.numbers_poll.get(choice) {
+            Some(PasswordBit::number_bit(number))
+        } else if let Some(text) = password_data.text_poll.get(choice - numbers) {
+            Some(PasswordBit::string_bit(text))
+        } else {
+            self.password_settings()
+                .symbol_set
+                .get(choice - numbers - texts)
+                .map(|symbol| PasswordBit::new(symbol.to_string(), "Symbol".to_string()))
+        }
+    }
+
+    /// Applies an edit to the bits of a generated password
+    fn edit_password(&self, generated: &GeneratedPassword, edit: Edit) -> GeneratedPassword {
+        let password_data = self.password_data();
+        let settings = self.password_settings();
+        let mut bits = generated.password.bits().to_vec();
+        let mut locked = generated.locked.clone();
+        // Regenerating without locks starts over from the generator, any other change keeps or adds hand picked bits
+        let edited = match edit {
+            Edit::ToggleLock(_) => generated.edited,
+            Edit::RegenerateUnlocked => locked.contains(&true),
+            _ => true,
+        };
+
+        let password = match edit {
+            Edit::Reroll(position) => {
+                password_data.reroll_bit(&generated.password, position, &settings)
+            }
+            Edit::ToggleLock(position) => {
+                locked[position] = !locked[position];
+                generated.password.clone()
+            }
+            Edit::Move { from, to } => {
+                let bit = bits.remove(from);
+                bits.insert(to, bit);
+                let bit_locked = locked.remove(from);
+                locked.insert(to, bit_locked);
+                Password::new(bits)
+            }
+            Edit::Remove(position) => {
+                bits.remove(position);
+                locked.remove(position);
+                Password::new(bits)
+            }
+            Edit::Add(choice) => {
+                bits.extend(self.chosen_bit(choice));
+                Password::new(bits)
+            }
+            Edit::RegenerateUnlocked => {
+                let fixed: Vec<Option<PasswordBit>> = bits
+                    .into_iter()
+                    .zip(&locked)
+                    .map(|(bit, &locked)| locked.then_some(bit))
+                    .collect();
+                password_data.generate_password_with_fixed(&settings, &fixed)
+            }
+        };
+
+        // Bits can't be generated without data, so a rerolled one may be left out
+        locked.resize(password.bit_count(), false);
+        self.generated_password(password, locked, edited)
+    }
+
+    /// The recipes of the practiced passwords due for a review, that the current data can rebuild
+    fn due_practices(&self) -> Vec<String> {
+        let password_data = self.password_data();
+        let settings = self.password_settings();
+        let now = now();
+        let mut due: Vec<String> = self
+            .practice_schedules
+            .iter()
+            .filter(|(_, schedule)| schedule.is_due(now) && !schedule.is_reliable())
+            .map(|(recipe, _)| recipe.clone())
+            .filter(|recipe| {
+                recipe
+                    .parse::<Recipe>()
+                    .and_then(|r| r.replay(&password_data, &settings))
+                    .is_ok()
+            })
+            .collect();
+        due.sort();
+        due
+    }
 }
 
 impl Component for FormComponent {
@@ -43,37 +410,74 @@ impl Component for FormComponent {
     type Properties = ();
 
     fn create(_ctx: &Context<Self>) -> Self {
-        Self {
+        let mut form = Self {
             numeric_values: vec![],
+            numeric_inputs: vec![],
             string_values: vec![],
             add_year: false,
             add_symbols: true,
+            keyboard_layouts: vec![],
             password_bits: 8,
+            symbol_set: SYMBOLS.to_vec(),
+            password_bits_input: 8.to_string(),
             passwords: None,
+            breach_list: None,
+            breach_status: None,
+            breach_reader: None,
+            practice: None,
+            practice_schedules: HashMap::new(),
+            storage_mode: StorageMode::Off,
+            storage_status: None,
+            vault: None,
+            sealed: None,
+            profile_status: None,
+            profile_reader: None,
+            pending_profile: None,
+            profile_url: None,
+        };
+        match storage::load() {
+            Saved::Nothing => {}
+            Saved::Sealed(sealed) => {
+                form.storage_mode = StorageMode::Locked;
+                form.sealed = Some(sealed);
+            }
+            Saved::Plain(saved) => {
+                form.restore(saved);
+                form.storage_mode = StorageMode::Plain;
+            }
         }
+        form
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::AddNumericInput => {
                 self.numeric_values.push(Number::default());
+                self.numeric_inputs
+                    .push(Number::default().value.to_string());
             }
             Msg::AddStringInput => {
                 self.string_values.push(String::new());
             }
             Msg::RemoveNumericInput(index) => {
                 self.numeric_values.remove(index);
+                self.numeric_inputs.remove(index);
             }
             Msg::RemoveStringInput(index) => {
                 self.string_values.remove(index);
             }
-            Msg::UpdateNumericValueInput(index, value) => {
-                self.numeric_values[index].value = value;
-                console::log_1(&format!("{:?}", self.numeric_values).as_str().into());
+            Msg::UpdateNumericValueInput(index, input) => {
+                if let Ok(value) = parse_number(&input, self.numeric_values[index].num_type) {
+                    self.numeric_values[index].value = value;
+                }
+                self.numeric_inputs[index] = input;
             }
             Msg::UpdateNumericTypeInput(index, value) => {
                 self.numeric_values[index].num_type = value;
-                console::log_1(&format!("{:?}", self.numeric_values).as_str().into());
+                // The same text can be valid for the new type, like a day typed before picking it
+                if let Ok(value) = parse_number(&self.numeric_inputs[index], value) {
+                    self.numeric_values[index].value = value;
+                }
             }
             Msg::UpdateStringInput(index, value) => {
                 self.string_values[index] = value;
@@ -84,37 +488,207 @@ impl Component for FormComponent {
             Msg::FlipAddSymbols => {
                 self.add_symbols = !self.add_symbols;
             }
-            Msg::UpdatePasswordBits(vaue) => {
-                self.password_bits = vaue;
+            Msg::ToggleLayout(layout) => {
+                match self.keyboard_layouts.iter().position(|l| *l == layout) {
+                    Some(index) => {
+                        self.keyboard_layouts.remove(index);
+                    }
+                    None => self.keyboard_layouts.push(layout),
+                }
             }
-            Msg::GeneratePasswords => {
-                let mut passwords: Vec<PasswordBits> = vec![];
-                let numbers: Vec<Number> = {
-                    if self.add_year {
-                        let current_year = chrono::Local::now().year();
-                        vec![
-                            vec![Number::new(current_year as u16, NumberType::CurrentYear)],
-                            self.numeric_values.clone(),
-                        ]
-                        .concat()
-                    } else {
-                        self.numeric_values.clone()
+            Msg::UpdatePasswordBits(input) => {
+                if let Ok(bits) = parse_bits(&input) {
+                    self.password_bits = bits;
+                }
+                self.password_bits_input = input;
+            }
+            Msg::LoadBreachList(file) => {
+                if let Some(file) = file {
+                    let link = ctx.link().clone();
+                    self.breach_status = Some(format!("Reading {}...", file.name()));
+                    self.breach_reader =
+                        Some(gloo_file::callbacks::read_as_text(&file, move |contents| {
+                            link.send_message(Msg::BreachListLoaded(
+                                contents.map_err(|e| e.to_string()),
+                            ))
+                        }));
+                }
+            }
+            Msg::BreachListLoaded(contents) => {
+                self.breach_reader = None;
+                match contents.and_then(|contents| read_breach_list(&contents)) {
+                    Ok((breach_list, count)) => {
+                        self.breach_list = Some(breach_list);
+                        self.breach_status = Some(format!("{} leaked passwords loaded", count));
+                    }
+                    Err(error) => {
+                        self.breach_list = None;
+                        self.breach_status =
+                            Some(format!("The breach list could not be read: {}", error));
                     }
-                };
-                let password_data = PasswordData::new(numbers, self.string_values.clone());
-                let password_settings = GenerationSettings {
-                    length: self.password_bits, // Test value
-                    symbols: self.add_symbols,
-                };
+                }
+            }
+            Msg::ClearBreachList => {
+                self.breach_list = None;
+                self.breach_status = None;
+                self.breach_reader = None;
+            }
+            Msg::GeneratePasswords => {
+                if self.form_error().is_some() {
+                    return false;
+                }
+                let mut passwords: Vec<Password> = vec![];
+                let password_data = self.password_data();
+                let password_settings = self.password_settings();
 
-                // Generate the passwords
-                for _ in 0..10 {
-                    passwords.push(password_data.generate_password(&password_settings));
+                // Generate the passwords, leaving out the ones found in the breach list
+                match &self.breach_list {
+                    Some(breach_list) => {
+                        let mut breached = 0;
+                        for _ in 0..PASSWORDS_COUNT {
+                            match password_data.generate_unbreached_password(
+                                &password_settings,
+                                breach_list.as_ref(),
+                                BREACH_ATTEMPTS,
+                            ) {
+                                Ok(password) => passwords.push(password),
+                                Err(_) => breached += 1,
+                            }
+                        }
+                        self.breach_status = Some(if breached == 0 {
+                            "None of the generated passwords are in the breach list".to_string()
+                        } else {
+                            format!("{} passwords could not be generated without matching the breach list", breached)
+                        });
+                    }
+                    None => {
+                        for _ in 0..PASSWORDS_COUNT {
+                            passwords.push(password_data.generate_password(&password_settings));
+                        }
+                    }
                 }
 
-                self.passwords = Some(passwords);
+                self.passwords = Some(
+                    passwords
+                        .into_iter()
+                        .map(|password| {
+                            let locked = vec![false; password.bit_count()];
+                            self.generated_password(password, locked, false)
+                        })
+                        .collect(),
+                );
+            }
+            Msg::EditPassword(index, edit) => {
+                if let Some(generated) = self.passwords.as_ref().and_then(|p| p.get(index)) {
+                    let edited = self.edit_password(generated, edit);
+                    if let Some(passwords) = &mut self.passwords {
+                        passwords[index] = edited;
+                    }
+                }
+            }
+            Msg::Practice(password) => {
+                // Passwords are practiced by recipe, so only where they come from is saved
+                if let Ok(recipe) =
+                    Recipe::record(&self.password_data(), &self.password_settings(), &password)
+                {
+                    self.practice = Some((password, recipe.to_string()));
+                }
+            }
+            Msg::ResumePractice(recipe) => {
+                if let Ok(password) = recipe
+                    .parse::<Recipe>()
+                    .and_then(|r| r.replay(&self.password_data(), &self.password_settings()))
+                {
+                    self.practice = Some((password, recipe));
+                }
+            }
+            Msg::RecordPractice(schedule) => {
+                if let Some((_, recipe)) = &self.practice {
+                    self.practice_schedules.insert(recipe.clone(), schedule);
+                }
+            }
+            Msg::ClosePractice => {
+                self.practice = None;
+            }
+            Msg::Remember(passphrase) => {
+                match passphrase {
+                    Some(passphrase) => {
+                        self.vault = Some(Vault::new(&passphrase));
+                        self.storage_mode = StorageMode::Encrypted;
+                    }
+                    None => self.storage_mode = StorageMode::Plain,
+                }
+                self.storage_status = None;
+            }
+            Msg::Unlock(passphrase) => {
+                if let Some(sealed) = &self.sealed {
+                    match storage::unlock(&passphrase, sealed) {
+                        Ok((vault, saved)) => {
+                            self.restore(saved);
+                            self.vault = Some(vault);
+                            self.sealed = None;
+                            self.storage_mode = StorageMode::Encrypted;
+                            self.storage_status = None;
+                        }
+                        Err(error) => self.storage_status = Some(error.to_string()),
+                    }
+                }
+            }
+            Msg::Forget => {
+                storage::forget();
+                self.restore(SavedForm::default());
+                self.passwords = None;
+                self.practice = None;
+                self.vault = None;
+                self.sealed = None;
+                self.storage_mode = StorageMode::Off;
+                self.storage_status = Some("Your data was wiped from this browser".to_string());
+            }
+            Msg::ExportProfile(passphrase) => {
+                let vault = passphrase.map(|passphrase| Vault::new(&passphrase));
+                let profile = Profile::from(&self.saved_form()).export(vault.as_ref());
+                self.profile_url = download(PROFILE_FILE_NAME, &profile);
+                self.profile_status = Some(match (&self.profile_url, &vault) {
+                    (None, _) => "The profile could not be downloaded".to_string(),
+                    (Some(_), Some(_)) => "Encrypted profile downloaded".to_string(),
+                    (Some(_), None) => "Profile downloaded, it is not encrypted".to_string(),
+                });
+            }
+            Msg::LoadProfile(file) => {
+                if let Some(file) = file {
+                    let link = ctx.link().clone();
+                    self.profile_status = Some(format!("Reading {}...", file.name()));
+                    self.profile_reader =
+                        Some(gloo_file::callbacks::read_as_text(&file, move |contents| {
+                            link.send_message(Msg::ProfileLoaded(
+                                contents.map_err(|e| e.to_string()),
+                            ))
+                        }));
+                }
+            }
+            Msg::ProfileLoaded(contents) => {
+                self.profile_reader = None;
+                match contents {
+                    Ok(contents) => self.import_profile(contents, None),
+                    Err(error) => {
+                        self.profile_status =
+                            Some(format!("The profile could not be read: {}", error))
+                    }
+                }
+            }
+            Msg::UnlockProfile(passphrase) => {
+                if let Some(contents) = self.pending_profile.take() {
+                    self.import_profile(contents, Some(&passphrase));
+                }
             }
         }
+
+        if matches!(
+            self.storage_mode,
+            StorageMode::Encrypted | StorageMode::Plain
+        ) {
+            storage::save(&self.saved_form(), self.vault.as_ref());
+        }
         true
     }
 
@@ -122,14 +696,17 @@ impl Component for FormComponent {
         let numeric_inputs = self
             .numeric_values
             .iter()
+            .zip(&self.numeric_inputs)
             .enumerate()
-            .map(|(index, number)| {
+            .map(|(index, (number, input))| {
                 html! {
                     <InputNumeric
-                        number={number.clone()}
+                        value={input.clone()}
+                        num_type={number.num_type}
+                        error={parse_number(input, number.num_type).err().map(AttrValue::from)}
                         oninput={ctx.link().callback(move |e: InputEvent| {
                             let input: HtmlInputElement = e.target_unchecked_into();
-                            Msg::UpdateNumericValueInput(index, input.value().parse().unwrap_or_default())
+                            Msg::UpdateNumericValueInput(index, input.value())
                         })}
                         onselect={ctx.link().callback(move |e: InputEvent| {
                             let input: HtmlInputElement = e.target_unchecked_into();
@@ -148,6 +725,7 @@ impl Component for FormComponent {
                 html! {
                     <InputString
                         string={string.clone()}
+                        error={text_error(&self.string_values, index).map(AttrValue::from)}
                         oninput={ctx.link().callback(move |e: InputEvent| {
                             let input: HtmlInputElement = e.target_unchecked_into();
                             Msg::UpdateStringInput(index, input.value())
@@ -157,74 +735,153 @@ impl Component for FormComponent {
                 }
             });
 
+        let due_practices = self.due_practices();
+        let bit_choices = self.bit_choices();
+        let form_error = self.form_error();
+
         html! {
-            <form onsubmit={|e: SubmitEvent| e.prevent_default()} class="w-full sm:max-w-screen-sm md:max-w-screen-md lg:max-w-screen-lg mx-auto px-4 sm:px-5 lg:px-8">
-                <div class="space-y-8">
-                    <div class="border-b border-gray-900/10 dark:border-gray-100/10 pb-8">
-                        <h2 class="text-base font-semibold leading-7 text-gray-900 dark:text-gray-100">{"Information"}</h2>
-                        <p class="mt-1 text-sm leading-6 text-gray-600 dark:text-gray-400">{"This information will be used to generate a memorable random password for you. This data is not leaving the browser."}</p>
-
-                        <div class="mt-10 grid grid-cols-1 gap-x-6 gap-y-8 sm:grid-cols-6">
-                            <div class="sm:col-span-6 lg:col-span-3">
-                                <h3 class="text-base font-semibold leading-7 text-gray-900 dark:text-gray-100">{"Numeric information"}</h3>
-
-                                <div class="flex flex-col gap-2">
-                                    { for numeric_inputs }
-                                </div>
+            <>
+                <form onsubmit={|e: SubmitEvent| e.prevent_default()} class="w-full sm:max-w-screen-sm md:max-w-screen-md lg:max-w-screen-lg mx-auto px-4 sm:px-5 lg:px-8">
+                    <div class="space-y-8">
+                        <InputStorage
+                            mode={self.storage_mode}
+                            status={self.storage_status.clone().map(AttrValue::from)}
+                            onremember={ctx.link().callback(Msg::Remember)}
+                            onunlock={ctx.link().callback(Msg::Unlock)}
+                            onforget={ctx.link().callback(|_| Msg::Forget)}
+                        />
 
-                                <button type="button" class="w-full polybius-button" onclick={ctx.link().callback(|_| Msg::AddNumericInput)}>
-                                    {"Add numeric value"}
-                                    <svg class="w-4 h-4 ml-2 ml-1" fill="none" stroke="currentColor" viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg">
-                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 6v6m0 0v6m0-6h6m-6 0H6"></path>
-                                    </svg>
-                                </button>
+                        <InputProfile
+                            status={self.profile_status.clone().map(AttrValue::from)}
+                            locked={self.pending_profile.is_some()}
+                            onexport={ctx.link().callback(Msg::ExportProfile)}
+                            onimport={ctx.link().callback(Msg::LoadProfile)}
+                            onunlock={ctx.link().callback(Msg::UnlockProfile)}
+                        />
 
-                                <ListTileSwitch title={"Add current year"} subtitle={"This will add the current year to the password numeric information"} checked={self.add_year} onclick={ctx.link().callback(|_| Msg::FlipAddYear)} />
-                            </div>
+                        <div class="border-b border-gray-900/10 dark:border-gray-100/10 pb-8">
+                            <h2 class="text-base font-semibold leading-7 text-gray-900 dark:text-gray-100">{"Information"}</h2>
+                            <p class="mt-1 text-sm leading-6 text-gray-600 dark:text-gray-400">{"This information will be used to generate a memorable random password for you. This data is not leaving the browser, and is only saved in it if you choose to remember it."}</p>
+
+                            <div class="mt-10 grid grid-cols-1 gap-x-6 gap-y-8 sm:grid-cols-6">
+                                <div class="sm:col-span-6 lg:col-span-3">
+                                    <h3 class="text-base font-semibold leading-7 text-gray-900 dark:text-gray-100">{"Numeric information"}</h3>
+
+                                    <div class="flex flex-col gap-2">
+                                        { for numeric_inputs }
+                                    </div>
 
-                            <div class="sm:col-span-6 lg:col-span-3">
-                                <h3 class="text-base font-semibold leading-7 text-gray-900 dark:text-gray-100">{"Text information"}</h3>
+                                    <button type="button" class="w-full polybius-button" onclick={ctx.link().callback(|_| Msg::AddNumericInput)}>
+                                        {"Add numeric value"}
+                                        <svg class="w-4 h-4 ml-2 ml-1" fill="none" stroke="currentColor" viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg">
+                                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 6v6m0 0v6m0-6h6m-6 0H6"></path>
+                                        </svg>
+                                    </button>
 
-                                <div class="flex flex-col gap-2">
-                                    { for string_inputs }
+                                    <ListTileSwitch title={"Add current year"} subtitle={"This will add the current year to the password numeric information"} checked={self.add_year} onclick={ctx.link().callback(|_| Msg::FlipAddYear)} />
                                 </div>
 
-                                <button type="button" class="w-full polybius-button" onclick={ctx.link().callback(|_| Msg::AddStringInput)}>
-                                    {"Add text value"}
-                                    <svg class="w-4 h-4 ml-2 ml-1" fill="none" stroke="currentColor" viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg">
-                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 6v6m0 0v6m0-6h6m-6 0H6"></path>
-                                    </svg>
-                                </button>
+                                <div class="sm:col-span-6 lg:col-span-3">
+                                    <h3 class="text-base font-semibold leading-7 text-gray-900 dark:text-gray-100">{"Text information"}</h3>
+
+                                    <div class="flex flex-col gap-2">
+                                        { for string_inputs }
+                                    </div>
+
+                                    <button type="button" class="w-full polybius-button" onclick={ctx.link().callback(|_| Msg::AddStringInput)}>
+                                        {"Add text value"}
+                                        <svg class="w-4 h-4 ml-2 ml-1" fill="none" stroke="currentColor" viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg">
+                                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 6v6m0 0v6m0-6h6m-6 0H6"></path>
+                                        </svg>
+                                    </button>
+
+                                    <ListTileSwitch title={"Add symbols"} subtitle={"This will add symbols like !@#$%^&*(). For more control you can add them manually"} checked={self.add_symbols} onclick={ctx.link().callback(|_| Msg::FlipAddSymbols)} />
 
-                                <ListTileSwitch title={"Add symbols"} subtitle={"This will add symbols like !@#$%^&*(). For more control you can add them manually"} checked={self.add_symbols} onclick={ctx.link().callback(|_| Msg::FlipAddSymbols)} />
+                                    if self.add_symbols {
+                                        <InputLayouts selected={self.keyboard_layouts.clone()} ontoggle={ctx.link().callback(Msg::ToggleLayout)} />
+                                    }
+                                </div>
                             </div>
                         </div>
-                    </div>
 
-                    <InputBits bits={self.password_bits} oninput={ctx.link().callback(move |e: InputEvent| {
-                        let input: HtmlInputElement = e.target_unchecked_into();
-                        Msg::UpdatePasswordBits(input.value().parse().unwrap_or(8))
-                    })}/>
+                        <InputBits
+                            bits={self.password_bits_input.clone()}
+                            error={parse_bits(&self.password_bits_input).err().map(AttrValue::from)}
+                            oninput={ctx.link().callback(move |e: InputEvent| {
+                            let input: HtmlInputElement = e.target_unchecked_into();
+                            Msg::UpdatePasswordBits(input.value())
+                        })}/>
 
-                    <div class="pb-8 flex justify-end">
-                        <button
-                            class="polybius-button "
-                            onclick={ctx.link().callback(|_| {
-                                Msg::GeneratePasswords
+                        <InputBreachList
+                            status={self.breach_status.clone().map(AttrValue::from)}
+                            loaded={self.breach_list.is_some()}
+                            onchange={ctx.link().callback(|e: Event| {
+                                let input: HtmlInputElement = e.target_unchecked_into();
+                                Msg::LoadBreachList(input.files().and_then(|files| files.get(0)).map(File::from))
                             })}
-                        >
-                            {"Generate passwords"}
-                        </button>
-                    </div>
+                            onclear={ctx.link().callback(|_| Msg::ClearBreachList)}
+                        />
 
-                    if let Some(passwords) = &self.passwords {
-                        <div>
-                            { for passwords.iter().map(|password| html! { <p class="dark:text-gray-100">{ password.iter().map(|e| e.bits.to_string()).collect::<String>() }</p> }) }
+                        <div class="pb-8 flex items-center justify-end gap-2">
+                            if let Some(error) = &form_error {
+                                <p class="mt-4 text-sm text-red-600 dark:text-red-400">{ error }</p>
+                            }
+                            if !due_practices.is_empty() {
+                                <button
+                                    type="button"
+                                    class="polybius-button"
+                                    onclick={ctx.link().callback(move |_| Msg::ResumePractice(due_practices[0].clone()))}
+                                >
+                                    {"Practice due"}
+                                </button>
+                            }
+                            <button
+                                class="polybius-button disabled:opacity-50 disabled:cursor-not-allowed"
+                                disabled={form_error.is_some()}
+                                onclick={ctx.link().callback(|_| {
+                                    Msg::GeneratePasswords
+                                })}
+                            >
+                                {"Generate passwords"}
+                            </button>
                         </div>
-                    }
 
-                </div>
-            </form>
+                        if let Some(passwords) = &self.passwords {
+                            <div>
+                                { for passwords.iter().enumerate().map(|(index, generated)| {
+                                    let practiced = generated.password.clone();
+                                    html! {
+                                        <PasswordResult
+                                            password={generated.password.clone()}
+                                            strength={generated.strength}
+                                            typing_cost={generated.typing_cost}
+                                            locked={generated.locked.clone()}
+                                            edited={generated.edited}
+                                            choices={bit_choices.clone()}
+                                            onedit={ctx.link().callback(move |edit| Msg::EditPassword(index, edit))}
+                                            onpractice={ctx.link().callback(move |_| Msg::Practice(practiced.clone()))}
+                                        />
+                                    }
+                                }) }
+                            </div>
+                        }
+
+                    </div>
+                </form>
+
+                if let Some((password, recipe)) = &self.practice {
+                    <div class="w-full sm:max-w-screen-sm md:max-w-screen-md lg:max-w-screen-lg mx-auto px-4 sm:px-5 lg:px-8">
+                        <Trainer
+                            key={recipe.clone()}
+                            password={password.clone()}
+                            schedule={self.practice_schedules.get(recipe).copied()}
+                            remember={matches!(self.storage_mode, StorageMode::Encrypted | StorageMode::Plain)}
+                            onrecord={ctx.link().callback(Msg::RecordPractice)}
+                            onclose={ctx.link().callback(|_| Msg::ClosePractice)}
+                        />
+                    </div>
+                }
+            </>
         }
     }
 }
@@ -2,7 +2,9 @@ use yew::prelude::*;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct BitsInput {
-    pub bits: usize,
+    /// The text typed, which may not be a valid number of bits
+    pub bits: AttrValue,
+    pub error: Option<AttrValue>,
     pub oninput: Callback<InputEvent>,
 }
 
@@ -18,17 +20,21 @@ pub fn input_bits(props: &BitsInput) -> Html {
                     <label for="bits_count" class="block text-sm font-medium leading-6 text-gray-900 dark:text-gray-100">{"Password bits"}</label>
                     <span class="text-sm text-gray-600 dark:text-gray-400">{"Select the lenght of the generated passwords. A bit does not necesarly have a lenght of one character (default is 8)"}</span>
                     <div class="mt-2">
-                        <div class="flex rounded-md shadow-sm ring-1 ring-inset ring-gray-300 dark:ring-gray-700 focus-within:ring-2 focus-within:ring-inset focus-within:ring-primary-600 sm:max-w-md">
+                        <div class={classes!("flex", "rounded-md", "shadow-sm", "ring-1", "ring-inset", if props.error.is_some() { "ring-red-500 dark:ring-red-400" } else { "ring-gray-300 dark:ring-gray-700" }, "focus-within:ring-2", "focus-within:ring-inset", "focus-within:ring-primary-600", "sm:max-w-md")}>
                             <input
                                 id="bits_count"
                                 name="bits_count"
                                 type="number"
                                 class="block flex-1 pl-3 border-0 bg-transparent py-1.5 pl-1 text-gray-900 dark:text-gray-100 placeholder:text-gray-400 dark:placeholder:text-gray-600 focus:ring-0 sm:text-sm sm:leading-6"
                                 placeholder="Number of bits"
-                                value={value.to_string()}
+                                aria-invalid={props.error.is_some().to_string()}
+                                value={value}
                                 oninput={oninput}
                             />
                         </div>
+                        if let Some(error) = &props.error {
+                            <p class="mt-1 text-sm text-red-600 dark:text-red-400">{ error.clone() }</p>
+                        }
                     </div>
                 </div>
             </div>
@@ -0,0 +1,43 @@
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct BreachListInput {
+    #[prop_or_default]
+    pub status: Option<AttrValue>,
+    pub loaded: bool,
+    pub onchange: Callback<Event>,
+    pub onclear: Callback<MouseEvent>,
+}
+
+#[function_component(InputBreachList)]
+pub fn input_breach_list(props: &BreachListInput) -> Html {
+    let onchange = props.onchange.clone();
+    let onclear = props.onclear.clone();
+
+    html! {
+        <div class="border-b border-gray-900/10 dark:border-gray-100/10 pb-8">
+            <div class="grid grid-cols-1 gap-x-6 gap-y-8 sm:grid-cols-6">
+                <div class="sm:col-span-4">
+                    <label for="breach_list" class="block text-sm font-medium leading-6 text-gray-900 dark:text-gray-100">{"Breach list"}</label>
+                    <span class="text-sm text-gray-600 dark:text-gray-400">{"Optionally load a list of leaked passwords, as a plain wordlist or as Have I Been Pwned SHA1:COUNT lines. Generated passwords found in it are thrown away. The file is only read by the browser."}</span>
+                    <div class="mt-2 flex items-center gap-2">
+                        <input
+                            id="breach_list"
+                            name="breach_list"
+                            type="file"
+                            accept=".txt,text/plain"
+                            class="block flex-1 text-sm text-gray-900 dark:text-gray-100"
+                            onchange={onchange}
+                        />
+                        if props.loaded {
+                            <button type="button" class="polybius-button" onclick={onclear}>{"Remove"}</button>
+                        }
+                    </div>
+                    if let Some(status) = &props.status {
+                        <p class="mt-2 text-sm text-gray-600 dark:text-gray-400">{status.clone()}</p>
+                    }
+                </div>
+            </div>
+        </div>
+    }
+}
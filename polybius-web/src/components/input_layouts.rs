@@ -0,0 +1,46 @@
+use polybius_lib::password_layouts::{common_symbols, KeyboardLayout};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct LayoutsInput {
+    pub selected: Vec<KeyboardLayout>,
+    pub ontoggle: Callback<KeyboardLayout>,
+}
+
+#[function_component(InputLayouts)]
+pub fn input_layouts(props: &LayoutsInput) -> Html {
+    let buttons = KeyboardLayout::ALL.into_iter().map(|layout| {
+        let selected = props.selected.contains(&layout);
+        let ontoggle = props.ontoggle.clone();
+        let color = if selected {
+            "bg-primary-500 text-primary-50 dark:bg-primary-300 dark:text-primary-1000"
+        } else {
+            "bg-gray-200 text-gray-900 dark:bg-gray-800 dark:text-gray-100"
+        };
+        html! {
+            <button
+                type="button"
+                class={classes!("rounded-md", "px-2", "py-1", "text-sm", color)}
+                aria-pressed={selected.to_string()}
+                onclick={move |_| ontoggle.emit(layout)}
+            >
+                { layout.to_string() }
+            </button>
+        }
+    });
+
+    let symbols: String = common_symbols(&props.selected).into_iter().collect();
+
+    html! {
+        <div class="mt-4">
+            <span class="text-sm font-medium leading-6 text-gray-900 dark:text-gray-100">{"Keyboard layouts"}</span>
+            <span class="block text-sm text-gray-600 dark:text-gray-400">{"Only use the symbols typable without AltGr on every selected layout"}</span>
+            <div class="mt-2 flex flex-wrap gap-2">
+                { for buttons }
+            </div>
+            if !props.selected.is_empty() {
+                <span class="block mt-2 text-sm font-mono text-gray-600 dark:text-gray-400">{ symbols }</span>
+            }
+        </div>
+    }
+}
@@ -1,11 +1,14 @@
-use polybius_lib::password_data::{Number, NumberType};
+use polybius_lib::password_data::NumberType;
 use yew::prelude::*;
 
 use crate::traits::data_serialization::DataSerialization;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct NumericInput {
-    pub number: Number,
+    /// The text typed, which may not be a valid number
+    pub value: AttrValue,
+    pub num_type: NumberType,
+    pub error: Option<AttrValue>,
     pub oninput: Callback<InputEvent>,
     pub onselect: Callback<InputEvent>,
     pub ondelete: Callback<MouseEvent>,
@@ -13,24 +16,25 @@ pub struct NumericInput {
 
 #[function_component(InputNumeric)]
 pub fn input_numeric(props: &NumericInput) -> Html {
-    let value = props.number.clone();
     let oninput = props.oninput.clone();
     let onselect = props.onselect.clone();
     let ondelete = props.ondelete.clone();
 
     let num_type_options = [NumberType::RelevantNumber, NumberType::BirthYear, NumberType::BirthMonth, NumberType::BirthDay].into_iter().map(|num_type| {
         html! {
-            <option class="text-primary-900" value={num_type.into_string()} selected={value.num_type == num_type}>{num_type.to_string()}</option>
+            <option class="text-primary-900" value={num_type.into_string()} selected={props.num_type == num_type}>{num_type.to_string()}</option>
         }
     });
 
     html! {
+        <div>
         <div class="relative ">
             <input type="number"
             name="number"
-            class="polybius-input"
+            class={classes!("polybius-input", props.error.is_some().then_some("ring-red-500 dark:ring-red-400"))}
             placeholder="0000"
-            value={value.value.to_string()}
+            aria-invalid={props.error.is_some().to_string()}
+            value={props.value.clone()}
             oninput={oninput}
             />
             <div class="absolute inset-y-0 right-10 flex items-center">
@@ -50,5 +54,9 @@ pub fn input_numeric(props: &NumericInput) -> Html {
                 </svg>
             </button>
         </div>
+        if let Some(error) = &props.error {
+            <p class="mt-1 text-sm text-red-600 dark:text-red-400">{ error.clone() }</p>
+        }
+        </div>
     }
 }
@@ -0,0 +1,135 @@
+use gloo_file::File;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ProfileInput {
+    #[prop_or_default]
+    pub status: Option<AttrValue>,
+    /// An encrypted profile was loaded and waits for its passphrase
+    pub locked: bool,
+    /// Downloads the profile, encrypted with the passphrase when there is one
+    pub onexport: Callback<Option<String>>,
+    pub onimport: Callback<Option<File>>,
+    pub onunlock: Callback<String>,
+}
+
+#[function_component(InputProfile)]
+pub fn input_profile(props: &ProfileInput) -> Html {
+    let passphrase = use_state(String::new);
+    let dragging = use_state(|| false);
+
+    let oninput = {
+        let passphrase = passphrase.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            passphrase.set(input.value());
+        })
+    };
+
+    let onexport = {
+        let passphrase = passphrase.clone();
+        let onexport = props.onexport.clone();
+        Callback::from(move |_| {
+            onexport.emit(Some((*passphrase).clone()).filter(|p| !p.is_empty()));
+            passphrase.set(String::new());
+        })
+    };
+
+    let onunlock = {
+        let passphrase = passphrase.clone();
+        let onunlock = props.onunlock.clone();
+        Callback::from(move |_| {
+            onunlock.emit((*passphrase).clone());
+            passphrase.set(String::new());
+        })
+    };
+
+    let onchange = {
+        let onimport = props.onimport.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            onimport.emit(input.files().and_then(|files| files.get(0)).map(File::from));
+        })
+    };
+
+    let ondragover = {
+        let dragging = dragging.clone();
+        Callback::from(move |e: DragEvent| {
+            e.prevent_default();
+            dragging.set(true);
+        })
+    };
+
+    let ondrop = {
+        let dragging = dragging.clone();
+        let onimport = props.onimport.clone();
+        Callback::from(move |e: DragEvent| {
+            e.prevent_default();
+            dragging.set(false);
+            let file = e
+                .data_transfer()
+                .and_then(|data| data.files())
+                .and_then(|files| files.get(0));
+            onimport.emit(file.map(File::from));
+        })
+    };
+
+    let ondragleave = {
+        let dragging = dragging.clone();
+        Callback::from(move |_: DragEvent| dragging.set(false))
+    };
+
+    let drop_zone = if *dragging {
+        "border-primary-500 bg-primary-50 dark:bg-primary-950"
+    } else {
+        "border-gray-300 dark:border-gray-700"
+    };
+
+    html! {
+        <div class="border-b border-gray-900/10 dark:border-gray-100/10 pb-8">
+            <div class="grid grid-cols-1 gap-x-6 gap-y-8 sm:grid-cols-6">
+                <div class="sm:col-span-4">
+                    <label for="profile_passphrase" class="block text-sm font-medium leading-6 text-gray-900 dark:text-gray-100">{"Profile"}</label>
+                    <span class="text-sm text-gray-600 dark:text-gray-400">{"Download your information as a file to load it in another browser. With a passphrase the file is encrypted, and the same passphrase is needed to load it."}</span>
+                    <div class="mt-2 flex gap-2">
+                        <input
+                            id="profile_passphrase"
+                            type="password"
+                            class="polybius-input"
+                            placeholder={if props.locked { "Passphrase of the profile" } else { "Passphrase (optional)" }}
+                            autocomplete="off"
+                            value={(*passphrase).clone()}
+                            oninput={oninput}
+                        />
+                        if props.locked {
+                            <button type="button" class="polybius-button" disabled={passphrase.is_empty()} onclick={onunlock}>{"Unlock"}</button>
+                        } else {
+                            <button type="button" class="polybius-button" onclick={onexport}>{"Download"}</button>
+                        }
+                    </div>
+                    <label
+                        for="profile_file"
+                        class={classes!("mt-4", "flex", "flex-col", "items-center", "gap-2", "rounded-xl", "border-2", "border-dashed", "p-4", "text-sm", "text-gray-600", "dark:text-gray-400", "cursor-pointer", drop_zone)}
+                        {ondragover}
+                        {ondragleave}
+                        {ondrop}
+                    >
+                        {"Drop a profile here or choose a file"}
+                        <input
+                            id="profile_file"
+                            name="profile_file"
+                            type="file"
+                            accept=".json,application/json"
+                            class="sr-only"
+                            onchange={onchange}
+                        />
+                    </label>
+                    if let Some(status) = &props.status {
+                        <p class="mt-2 text-sm text-gray-600 dark:text-gray-400">{ status.clone() }</p>
+                    }
+                </div>
+            </div>
+        </div>
+    }
+}
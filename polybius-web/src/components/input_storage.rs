@@ -0,0 +1,100 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// Whether and how the form is saved in the browser
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StorageMode {
+    Off,
+    /// Saved encrypted, waiting for the passphrase
+    Locked,
+    Encrypted,
+    Plain,
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct StorageInput {
+    pub mode: StorageMode,
+    #[prop_or_default]
+    pub status: Option<AttrValue>,
+    /// Starts saving the form, encrypted with the passphrase when there is one
+    pub onremember: Callback<Option<String>>,
+    pub onunlock: Callback<String>,
+    pub onforget: Callback<MouseEvent>,
+}
+
+#[function_component(InputStorage)]
+pub fn input_storage(props: &StorageInput) -> Html {
+    let passphrase = use_state(String::new);
+
+    let oninput = {
+        let passphrase = passphrase.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            passphrase.set(input.value());
+        })
+    };
+
+    let onsubmit = {
+        let passphrase = passphrase.clone();
+        let mode = props.mode;
+        let onremember = props.onremember.clone();
+        let onunlock = props.onunlock.clone();
+        Callback::from(move |_| {
+            if mode == StorageMode::Locked {
+                onunlock.emit((*passphrase).clone());
+            } else {
+                onremember.emit(Some((*passphrase).clone()));
+            }
+            passphrase.set(String::new());
+        })
+    };
+
+    let onplain = {
+        let onremember = props.onremember.clone();
+        Callback::from(move |_| onremember.emit(None))
+    };
+
+    let description = match props.mode {
+        StorageMode::Off => "Save the information in this browser, encrypted with a passphrase, to find it on your next visit",
+        StorageMode::Locked => "Your saved information is encrypted, enter the passphrase to load it",
+        StorageMode::Encrypted => "Your information is saved encrypted in this browser",
+        StorageMode::Plain => "Your information is saved unencrypted in this browser",
+    };
+
+    html! {
+        <div class="border-b border-gray-900/10 dark:border-gray-100/10 pb-8">
+            <div class="grid grid-cols-1 gap-x-6 gap-y-8 sm:grid-cols-6">
+                <div class="sm:col-span-4">
+                    <label for="passphrase" class="block text-sm font-medium leading-6 text-gray-900 dark:text-gray-100">{"Remember my data"}</label>
+                    <span class="text-sm text-gray-600 dark:text-gray-400">{ description }</span>
+                    if matches!(props.mode, StorageMode::Off | StorageMode::Locked) {
+                        <div class="mt-2 flex gap-2">
+                            <input
+                                id="passphrase"
+                                type="password"
+                                class="polybius-input"
+                                placeholder="Passphrase"
+                                autocomplete="off"
+                                value={(*passphrase).clone()}
+                                oninput={oninput}
+                            />
+                            <button type="button" class="polybius-button" disabled={passphrase.is_empty()} onclick={onsubmit}>
+                                { if props.mode == StorageMode::Locked { "Unlock" } else { "Remember" } }
+                            </button>
+                        </div>
+                    }
+                    if let Some(status) = &props.status {
+                        <span class="block mt-2 text-sm text-gray-600 dark:text-gray-400">{ status.clone() }</span>
+                    }
+                    <div class="mt-2 flex gap-4">
+                        if props.mode == StorageMode::Off {
+                            <button type="button" class="text-sm text-primary-500" onclick={onplain}>{"Remember without encryption"}</button>
+                        } else {
+                            <button type="button" class="text-sm text-primary-500" onclick={props.onforget.clone()}>{"Forget my data"}</button>
+                        }
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -3,6 +3,7 @@ use yew::prelude::*;
 #[derive(Properties, PartialEq, Clone)]
 pub struct StringInput {
     pub string: String,
+    pub error: Option<AttrValue>,
     pub oninput: Callback<InputEvent>,
     pub ondelete: Callback<MouseEvent>,
 }
@@ -14,10 +15,12 @@ pub fn input_string(props: &StringInput) -> Html {
     let ondelete = props.ondelete.clone();
 
     html! {
+        <div>
         <div class="relative ">
             <input type="text"
-                class="polybius-input"
+                class={classes!("polybius-input", props.error.is_some().then_some("ring-red-500 dark:ring-red-400"))}
                 placeholder="Text value"
+                aria-invalid={props.error.is_some().to_string()}
                 value={value.clone()}
                 oninput={oninput}
             />
@@ -31,5 +34,9 @@ pub fn input_string(props: &StringInput) -> Html {
                 </svg>
             </button>
         </div>
+        if let Some(error) = &props.error {
+            <p class="mt-1 text-sm text-red-600 dark:text-red-400">{ error.clone() }</p>
+        }
+        </div>
     }
 }
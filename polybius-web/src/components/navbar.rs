@@ -1,6 +1,6 @@
 use yew::prelude::*;
 
-use crate::components::github_logo::GitHubLogo;
+use crate::components::{github_logo::GitHubLogo, offline_indicator::OfflineIndicator};
 
 #[function_component]
 pub fn NavBar() -> Html {
@@ -15,9 +15,12 @@ pub fn NavBar() -> Html {
                                 <span class="text-primary-400 dark:text-primary-400 text-2xl font-display font-bold hover:text-3xl transition-all ease px-2"> { "#%&*" } </span>
                             </div>
                         </div>
-                        <a class="text-primary-1000 dark:text-primary-50 absolute right-2 flex items-center justify-center h-6 w-6" href="https://github.com/2-5-perceivers/polybius">
-                            <GitHubLogo />
-                        </a>
+                        <div class="absolute right-2 flex items-center gap-3">
+                            <OfflineIndicator />
+                            <a class="text-primary-1000 dark:text-primary-50 flex items-center justify-center h-6 w-6" href="https://github.com/2-5-perceivers/polybius">
+                                <GitHubLogo />
+                            </a>
+                        </div>
                     </div>
                 </div>
             </nav>
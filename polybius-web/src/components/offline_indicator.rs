@@ -0,0 +1,32 @@
+use web_sys::console;
+use yew::{platform::spawn_local, prelude::*};
+
+use crate::service_worker;
+
+#[function_component(OfflineIndicator)]
+pub fn offline_indicator() -> Html {
+    let ready = use_state(|| false);
+
+    {
+        let ready = ready.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                match service_worker::register().await {
+                    Ok(()) => ready.set(true),
+                    Err(error) => console::warn_2(&"Polybius can't work offline:".into(), &error),
+                }
+            });
+        });
+    }
+
+    html! {
+        if *ready {
+            <span
+                class="rounded-full px-2 py-0.5 text-xs font-semibold bg-primary-200 text-primary-900 dark:bg-primary-800 dark:text-primary-50"
+                title="Polybius is saved in this browser and works without a network"
+            >
+                {"Offline ready"}
+            </span>
+        }
+    }
+}
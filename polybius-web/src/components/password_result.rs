@@ -0,0 +1,215 @@
+use polybius_lib::{password::Password, password_strength::Strength, password_typing::TypingCost};
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+/// The entropy filling a strength bar
+const FULL_BAR_ENTROPY: f64 = 100.0;
+
+/// The colours the bits of a password cycle through
+const BIT_COLORS: [&str; 4] = [
+    "bg-primary-200 text-primary-1000 dark:bg-primary-800 dark:text-primary-50",
+    "bg-amber-200 text-amber-900 dark:bg-amber-800 dark:text-amber-50",
+    "bg-sky-200 text-sky-900 dark:bg-sky-800 dark:text-sky-50",
+    "bg-emerald-200 text-emerald-900 dark:bg-emerald-800 dark:text-emerald-50",
+];
+
+/// A change to the bits of a password, by their position
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Edit {
+    Reroll(usize),
+    ToggleLock(usize),
+    Move {
+        from: usize,
+        to: usize,
+    },
+    Remove(usize),
+    /// Adds the bit of one of the choices at the end
+    Add(usize),
+    /// Generates again the bits that are not locked
+    RegenerateUnlocked,
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct PasswordResultProps {
+    pub password: Password,
+    pub strength: Strength,
+    /// The taps to type the password on a phone keyboard
+    pub typing_cost: TypingCost,
+    /// Whether each bit is kept when regenerating
+    pub locked: Vec<bool>,
+    /// Whether bits were picked by hand, leaving the targeted strength unknown
+    pub edited: bool,
+    /// The items that can be added by hand
+    pub choices: Vec<AttrValue>,
+    pub onedit: Callback<Edit>,
+    pub onpractice: Callback<MouseEvent>,
+}
+
+/// A bar filling up with the entropy, red under 28 bits and green from 60. `unknown` is shown without an entropy.
+fn strength_bar(label: &str, entropy: Option<f64>, unknown: &str) -> Html {
+    let (width, color, text) = match entropy {
+        Some(entropy) => {
+            let color = match entropy {
+                e if e < 28.0 => "bg-red-500",
+                e if e < 60.0 => "bg-amber-500",
+                _ => "bg-green-500",
+            };
+            let width = (entropy / FULL_BAR_ENTROPY).clamp(0.0, 1.0) * 100.0;
+            (width, color, format!("{:.0} bits", entropy))
+        }
+        None => (0.0, "bg-gray-500", unknown.to_string()),
+    };
+
+    html! {
+        <div class="flex items-center gap-2 text-xs text-gray-600 dark:text-gray-400">
+            <span class="w-16 shrink-0">{ label }</span>
+            <div class="h-2 grow rounded-full bg-gray-200 dark:bg-gray-800">
+                <div class={classes!("h-2", "rounded-full", color)} style={format!("width: {:.0}%", width)} />
+            </div>
+            <span class="w-32 shrink-0 text-right">{ text }</span>
+        </div>
+    }
+}
+
+#[function_component(PasswordResult)]
+pub fn password_result(props: &PasswordResultProps) -> Html {
+    let expanded = use_state(|| false);
+    let editing = use_state(|| false);
+    // The position of the bit being dragged
+    let dragged = use_state(|| None::<usize>);
+
+    let ontoggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_| expanded.set(!*expanded))
+    };
+    let onedittoggle = {
+        let editing = editing.clone();
+        Callback::from(move |_| editing.set(!*editing))
+    };
+    let onadd = {
+        let onedit = props.onedit.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Ok(choice) = select.value().parse() {
+                onedit.emit(Edit::Add(choice));
+            }
+            select.set_value("");
+        })
+    };
+
+    let bits = props.password.bits().iter().enumerate().map(|(index, bit)| {
+        let locked = props.locked.get(index).copied().unwrap_or(false);
+        let edit = |edit: Edit| {
+            let onedit = props.onedit.clone();
+            Callback::from(move |_: MouseEvent| onedit.emit(edit))
+        };
+        let ondragstart = {
+            let dragged = dragged.clone();
+            Callback::from(move |e: DragEvent| {
+                // Firefox only starts dragging when there is some data
+                if let Some(data) = e.data_transfer() {
+                    let _ = data.set_data("text/plain", "");
+                }
+                dragged.set(Some(index));
+            })
+        };
+        let ondrop = {
+            let dragged = dragged.clone();
+            let onedit = props.onedit.clone();
+            Callback::from(move |e: DragEvent| {
+                e.prevent_default();
+                if let Some(from) = *dragged {
+                    if from != index {
+                        onedit.emit(Edit::Move { from, to: index });
+                    }
+                }
+                dragged.set(None);
+            })
+        };
+        let title = if locked {
+            format!("{} (locked)", bit.importance)
+        } else {
+            format!("{} (click to reroll, drag to move)", bit.importance)
+        };
+
+        html! {
+            <span
+                class={classes!("inline-flex", "flex-col", "items-center", "rounded-md", "px-1", "cursor-move", BIT_COLORS[index % BIT_COLORS.len()], locked.then_some("ring-2 ring-primary-500"))}
+                draggable="true"
+                {ondragstart}
+                ondragover={|e: DragEvent| e.prevent_default()}
+                {ondrop}
+            >
+                <button type="button" class="font-mono font-semibold" {title} disabled={locked} onclick={edit(Edit::Reroll(index))}>
+                    { bit.bits.clone() }
+                </button>
+                if *expanded {
+                    <span class="text-xs">{ bit.importance.clone() }</span>
+                }
+                if *editing {
+                    <span class="flex gap-1 text-xs">
+                        <button type="button" aria-pressed={locked.to_string()} onclick={edit(Edit::ToggleLock(index))}>
+                            { if locked { "Unlock" } else { "Lock" } }
+                        </button>
+                        <button type="button" aria-label="Remove" onclick={edit(Edit::Remove(index))}>{"×"}</button>
+                    </span>
+                }
+            </span>
+        }
+    });
+
+    let choices = props.choices.iter().enumerate().map(|(index, choice)| {
+        html! { <option value={index.to_string()}>{ choice.clone() }</option> }
+    });
+
+    html! {
+        <div class="py-3 border-b border-gray-900/5 dark:border-gray-100/5">
+            <div class="flex items-center justify-between gap-2">
+                <p class="flex flex-wrap gap-0.5 dark:text-gray-100">{ for bits }</p>
+                <div class="flex shrink-0 gap-4">
+                    <span class="text-sm text-gray-600 dark:text-gray-400">{ format!("{} characters", props.password.len()) }</span>
+                    <span class="text-sm text-gray-600 dark:text-gray-400" title="Taps on a phone keyboard, with the layer switches">
+                        { format!("{} taps", props.typing_cost.taps) }
+                    </span>
+                    <button type="button" class="text-sm text-primary-500" aria-expanded={expanded.to_string()} onclick={ontoggle}>
+                        { if *expanded { "Less" } else { "Explain" } }
+                    </button>
+                    <button type="button" class="text-sm text-primary-500" aria-expanded={editing.to_string()} onclick={onedittoggle}>
+                        { if *editing { "Done" } else { "Edit" } }
+                    </button>
+                    <button type="button" class="text-sm text-primary-500" onclick={props.onpractice.clone()}>
+                        {"Practice"}
+                    </button>
+                </div>
+            </div>
+            if *editing {
+                <div class="mt-2 flex flex-wrap items-center gap-2">
+                    <select class="polybius-input" aria-label="Add a bit" onchange={onadd}>
+                        <option value="" selected=true>{"Add..."}</option>
+                        { for choices }
+                    </select>
+                    <button type="button" class="polybius-button" onclick={
+                        let onedit = props.onedit.clone();
+                        move |_| onedit.emit(Edit::RegenerateUnlocked)
+                    }>
+                        {"Regenerate unlocked"}
+                    </button>
+                </div>
+            }
+            <div class="mt-2 flex flex-col gap-1">
+                { strength_bar("Generic", Some(props.strength.generic_entropy), "") }
+                { strength_bar(
+                    "Targeted",
+                    props.strength.targeted_entropy,
+                    if props.edited { "Unknown once edited" } else { "Not made from the data" },
+                ) }
+            </div>
+            if *expanded {
+                <p class="mt-2 text-xs text-gray-600 dark:text-gray-400">
+                    {"Generic is against someone guessing any password with these kinds of characters. Targeted is against someone who knows your information and how Polybius works. "}
+                    { format!("Typing it on a phone takes {} taps, {} of them to switch between letters, digits and symbols.", props.typing_cost.taps, props.typing_cost.layer_switches) }
+                </p>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,140 @@
+use polybius_lib::{
+    password::Password,
+    password_practice::{check_attempt, PracticeSchedule},
+};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// The current time, in seconds
+pub fn now() -> u64 {
+    chrono::Utc::now().timestamp() as u64
+}
+
+/// Writes a wait in the largest unit that fits
+fn format_wait(seconds: u64) -> String {
+    match seconds {
+        0..=59 => format!("{} seconds", seconds),
+        60..=3599 => format!("{} minutes", seconds / 60),
+        3600..=86399 => format!("{} hours", seconds / 3600),
+        _ => format!("{} days", seconds / 86400),
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct TrainerProps {
+    pub password: Password,
+    /// The schedule of the password, when it was practiced before
+    pub schedule: Option<PracticeSchedule>,
+    /// Whether the schedule is saved in the browser, only when the user chose to remember the form
+    pub remember: bool,
+    /// Receives the schedule after each attempt, to keep it with the form
+    pub onrecord: Callback<PracticeSchedule>,
+    pub onclose: Callback<MouseEvent>,
+}
+
+#[function_component(Trainer)]
+pub fn trainer(props: &TrainerProps) -> Html {
+    let schedule = {
+        let saved = props.schedule;
+        use_state(move || saved.unwrap_or_else(|| PracticeSchedule::new(now())))
+    };
+    let attempt = use_state(String::new);
+    let feedback = use_state(|| None::<Vec<bool>>);
+    let revealed = use_state(|| false);
+
+    let oninput = {
+        let attempt = attempt.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            attempt.set(input.value());
+        })
+    };
+
+    let oncheck = {
+        let password = props.password.clone();
+        let onrecord = props.onrecord.clone();
+        let schedule = schedule.clone();
+        let attempt = attempt.clone();
+        let feedback = feedback.clone();
+        let revealed = revealed.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let correct = check_attempt(&password, &attempt);
+            let mut next = *schedule;
+            next.record(correct.iter().all(|&c| c), now());
+            onrecord.emit(next);
+            schedule.set(next);
+            feedback.set(Some(correct));
+            attempt.set(String::new());
+            revealed.set(false);
+        })
+    };
+
+    let onreveal = {
+        let revealed = revealed.clone();
+        Callback::from(move |_| revealed.set(!*revealed))
+    };
+
+    let status = if schedule.is_reliable() {
+        "You recall this password reliably".to_string()
+    } else if schedule.is_due(now()) {
+        "A review is due, type the password from memory".to_string()
+    } else {
+        format!(
+            "Next review in {}. Practicing before then only counts if you get it wrong",
+            format_wait(schedule.due.saturating_sub(now()))
+        )
+    };
+
+    let chips = props
+        .password
+        .bits()
+        .iter()
+        .enumerate()
+        .map(|(index, bit)| {
+            let color = match feedback.as_ref().map(|correct| correct[index]) {
+                Some(true) => "bg-green-200 text-green-900 dark:bg-green-900 dark:text-green-100",
+                Some(false) => "bg-red-200 text-red-900 dark:bg-red-900 dark:text-red-100",
+                None => "bg-gray-200 text-gray-900 dark:bg-gray-800 dark:text-gray-100",
+            };
+            html! {
+                <span class={classes!("rounded-md", "px-2", "py-1", "text-sm", color)}>
+                    if *revealed {
+                        <span class="font-mono font-semibold mr-1">{ bit.bits.clone() }</span>
+                    }
+                    { bit.importance.clone() }
+                </span>
+            }
+        });
+
+    html! {
+        <div class="border-b border-gray-900/10 dark:border-gray-100/10 pb-8">
+            <div class="flex items-center justify-between">
+                <h2 class="text-base font-semibold leading-7 text-gray-900 dark:text-gray-100">{"Practice"}</h2>
+                <button type="button" class="text-sm text-primary-500" onclick={props.onclose.clone()}>{"Close"}</button>
+            </div>
+            <p class="mt-1 text-sm leading-6 text-gray-600 dark:text-gray-400">{ status }</p>
+            if !props.remember {
+                <p class="mt-1 text-sm leading-6 text-gray-600 dark:text-gray-400">{"The schedule is kept for this visit only, remember your information to keep it"}</p>
+            }
+
+            <div class="mt-4 flex flex-wrap gap-2">
+                { for chips }
+            </div>
+
+            <form onsubmit={oncheck} class="mt-4 flex gap-2">
+                <input type="password"
+                    class="polybius-input"
+                    placeholder="Type the password"
+                    autocomplete="off"
+                    value={(*attempt).clone()}
+                    oninput={oninput}
+                />
+                <button type="submit" class="polybius-button">{"Check"}</button>
+                <button type="button" class="polybius-button" onclick={onreveal}>
+                    { if *revealed { "Hide" } else { "Show" } }
+                </button>
+            </form>
+        </div>
+    }
+}
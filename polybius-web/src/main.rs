@@ -1,13 +1,23 @@
 mod app;
+mod service_worker;
+mod storage;
+mod validation;
 
 mod components {
     pub mod form;
     pub mod github_logo;
     pub mod input_bits;
+    pub mod input_breach_list;
+    pub mod input_layouts;
     pub mod input_numeric;
+    pub mod input_profile;
+    pub mod input_storage;
     pub mod input_string;
     pub mod list_tile_switch;
     pub mod navbar;
+    pub mod offline_indicator;
+    pub mod password_result;
+    pub mod trainer;
 }
 
 mod traits {
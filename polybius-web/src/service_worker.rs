@@ -0,0 +1,27 @@
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::Reflect,
+    wasm_bindgen::{JsCast, JsValue},
+    Navigator,
+};
+
+/// The service worker caching the app, copied next to `index.html` by Trunk
+const SERVICE_WORKER: &str = "sw.js";
+
+/// Registers the service worker, returning once it is active and the app works offline
+pub async fn register() -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+    let navigator: Navigator = window.navigator();
+    // Only there in secure contexts, and not in every browser
+    if !Reflect::has(
+        navigator.unchecked_ref(),
+        &JsValue::from_str("serviceWorker"),
+    )? {
+        return Err(JsValue::from_str("Service workers are not supported"));
+    }
+
+    let container = navigator.service_worker();
+    JsFuture::from(container.register(SERVICE_WORKER)).await?;
+    JsFuture::from(container.ready()?).await?;
+    Ok(())
+}
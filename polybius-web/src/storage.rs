@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use gloo_storage::{LocalStorage, Storage};
+use polybius_lib::{
+    password_bits::SYMBOLS,
+    password_data::{Number, PasswordData},
+    password_generation::GenerationSettings,
+    password_layouts::KeyboardLayout,
+    password_practice::PracticeSchedule,
+    password_profile::Profile,
+    password_vault::{Vault, VaultError},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::validation::trimmed_texts;
+
+/// The local storage key of the saved form
+const FORM_KEY: &str = "polybius.form";
+
+/// The information of the form that is remembered between visits
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct SavedForm {
+    pub numeric_values: Vec<Number>,
+    pub string_values: Vec<String>,
+    pub add_year: bool,
+    pub add_symbols: bool,
+    pub password_bits: usize,
+    /// The symbols a symbol bit can be, before the keyboard layouts narrow them
+    pub symbol_set: Vec<char>,
+    pub keyboard_layouts: Vec<KeyboardLayout>,
+    /// The practice schedules, by the recipe of the password they are for. A recipe confirms guessed entries, so they
+    /// are saved with the data, and sealed with it when it is encrypted.
+    pub practice: HashMap<String, PracticeSchedule>,
+}
+
+impl Default for SavedForm {
+    fn default() -> Self {
+        Self {
+            numeric_values: vec![],
+            string_values: vec![],
+            add_year: false,
+            add_symbols: true,
+            password_bits: 8,
+            symbol_set: SYMBOLS.to_vec(),
+            keyboard_layouts: vec![],
+            practice: HashMap::new(),
+        }
+    }
+}
+
+impl From<&SavedForm> for Profile {
+    fn from(form: &SavedForm) -> Self {
+        let mut profile = Profile::new(
+            PasswordData::new(
+                form.numeric_values.clone(),
+                trimmed_texts(&form.string_values),
+            ),
+            GenerationSettings::builder()
+                .length(form.password_bits)
+                .symbols(form.add_symbols)
+                .symbol_set(form.symbol_set.iter().copied())
+                .build(),
+        );
+        profile.add_current_year = form.add_year;
+        profile.keyboard_layouts = form.keyboard_layouts.clone();
+        profile
+    }
+}
+
+impl From<Profile> for SavedForm {
+    fn from(profile: Profile) -> Self {
+        Self {
+            numeric_values: profile.data.numbers_poll,
+            string_values: profile.data.text_poll,
+            add_year: profile.add_current_year,
+            add_symbols: profile.settings.symbols,
+            password_bits: profile.settings.length,
+            symbol_set: profile.settings.symbol_set,
+            keyboard_layouts: profile.keyboard_layouts,
+            practice: HashMap::new(),
+        }
+    }
+}
+
+/// How the form is kept in the browser
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StoredForm {
+    /// Encrypted with a passphrase, see `Vault`
+    Sealed(String),
+    Plain(SavedForm),
+}
+
+/// What the browser has saved, before it is unlocked
+pub enum Saved {
+    Nothing,
+    Sealed(String),
+    Plain(SavedForm),
+}
+
+/// Reads the saved form, if any
+pub fn load() -> Saved {
+    match LocalStorage::get(FORM_KEY) {
+        Ok(StoredForm::Sealed(sealed)) => Saved::Sealed(sealed),
+        Ok(StoredForm::Plain(form)) => Saved::Plain(form),
+        Err(_) => Saved::Nothing,
+    }
+}
+
+/// Decrypts a saved form, returning the vault to save it again with
+pub fn unlock(passphrase: &str, sealed: &str) -> Result<(Vault, SavedForm), VaultError> {
+    let vault = Vault::unlock(passphrase, sealed)?;
+    let form = serde_json::from_slice(&vault.open(sealed)?).map_err(|_| VaultError::Malformed)?;
+    Ok((vault, form))
+}
+
+/// Saves the form, encrypted when there is a vault
+pub fn save(form: &SavedForm, vault: Option<&Vault>) {
+    let stored = match vault {
+        Some(vault) => match serde_json::to_vec(form) {
+            Ok(json) => StoredForm::Sealed(vault.seal(&json)),
+            Err(_) => return,
+        },
+        None => StoredForm::Plain(form.clone()),
+    };
+    let _ = LocalStorage::set(FORM_KEY, stored);
+}
+
+/// Wipes everything Polybius saved in the browser, the form along with the practice schedules
+pub fn forget() {
+    LocalStorage::delete(FORM_KEY);
+}
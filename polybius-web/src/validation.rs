@@ -0,0 +1,113 @@
+use polybius_lib::password_data::NumberType;
+
+/// The most bits a password can have, far more than anyone can remember
+pub const MAX_BITS: usize = 64;
+
+/// Parses a numeric value, checking days and months are in their range
+pub fn parse_number(input: &str, num_type: NumberType) -> Result<u16, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Enter a number".to_string());
+    }
+    if !input.chars().all(|c| c.is_ascii_digit()) {
+        return Err("Only whole positive numbers are allowed".to_string());
+    }
+    let value: u16 = input
+        .parse()
+        .map_err(|_| format!("The number can be at most {}", u16::MAX))?;
+
+    let range = match num_type {
+        NumberType::BirthDay => 1..=31,
+        NumberType::BirthMonth => 1..=12,
+        _ => return Ok(value),
+    };
+    if range.contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!(
+            "A {} is between {} and {}",
+            num_type.to_string().to_lowercase(),
+            range.start(),
+            range.end()
+        ))
+    }
+}
+
+/// The problem with the text at `index`, when it is empty or repeats an earlier one
+pub fn text_error(texts: &[String], index: usize) -> Option<String> {
+    let text = texts[index].trim();
+    if text.is_empty() {
+        return Some("The text is empty".to_string());
+    }
+    texts[..index]
+        .iter()
+        .any(|other| other.trim().eq_ignore_ascii_case(text))
+        .then(|| "This text is already in the list".to_string())
+}
+
+/// The texts without their leading and trailing spaces, as the passwords are generated from
+pub fn trimmed_texts(texts: &[String]) -> Vec<String> {
+    texts.iter().map(|text| text.trim().to_string()).collect()
+}
+
+/// Parses the number of bits of the passwords
+pub fn parse_bits(input: &str) -> Result<usize, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Enter a number of bits".to_string());
+    }
+    if !input.chars().all(|c| c.is_ascii_digit()) {
+        return Err("Only whole positive numbers are allowed".to_string());
+    }
+    match input.parse() {
+        Ok(0) => Err("A password needs at least one bit".to_string()),
+        Ok(bits) if bits <= MAX_BITS => Ok(bits),
+        // Too large for a usize too
+        _ => Err(format!("A password can have at most {} bits", MAX_BITS)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number(" 2005 ", NumberType::BirthYear), Ok(2005));
+        assert_eq!(parse_number("12", NumberType::BirthMonth), Ok(12));
+        assert_eq!(parse_number("31", NumberType::BirthDay), Ok(31));
+        assert!(parse_number("", NumberType::BirthYear).is_err());
+        assert!(parse_number("-3", NumberType::BirthYear).is_err());
+        assert!(parse_number("1.5", NumberType::BirthYear).is_err());
+        assert!(parse_number("65536", NumberType::BirthYear).is_err());
+        assert!(parse_number("13", NumberType::BirthMonth).is_err());
+        assert!(parse_number("0", NumberType::BirthDay).is_err());
+        assert!(parse_number("32", NumberType::BirthDay).is_err());
+    }
+
+    #[test]
+    fn test_text_error() {
+        let texts = ["Cats", " ", "cats ", "Lemons"].map(String::from);
+        assert_eq!(text_error(&texts, 0), None);
+        assert!(text_error(&texts, 1).is_some());
+        assert!(text_error(&texts, 2).is_some());
+        assert_eq!(text_error(&texts, 3), None);
+    }
+
+    #[test]
+    fn test_trimmed_texts() {
+        let texts = [" Cats", "Lemon tree ", "Apples"].map(String::from);
+        assert_eq!(trimmed_texts(&texts), ["Cats", "Lemon tree", "Apples"]);
+    }
+
+    #[test]
+    fn test_parse_bits() {
+        assert_eq!(parse_bits(" 6 "), Ok(6));
+        assert_eq!(parse_bits(&MAX_BITS.to_string()), Ok(MAX_BITS));
+        assert!(parse_bits("").is_err());
+        assert!(parse_bits("0").is_err());
+        assert!(parse_bits("-1").is_err());
+        assert!(parse_bits("65").is_err());
+        assert!(parse_bits("99999999999999999999999").is_err());
+    }
+}
@@ -0,0 +1,76 @@
+// Keeps Polybius working without a network. The page and every asset it links to, the wasm bundle included, are
+// cached on install, and everything is then served from the cache first. Bump the version to drop old caches.
+const CACHE = "polybius-v1";
+
+// Caches the page along with the same origin assets it links to, whose names Trunk hashes, when it changed. The
+// assets the previous page linked to, and anything else it doesn't, are then dropped.
+async function precache(cache, page) {
+  const html = await page.clone().text();
+  const cached = await cache.match("./");
+  if (cached && (await cached.text()) === html) {
+    return;
+  }
+
+  const pageUrl = new URL("./", self.registration.scope).href;
+  const assets = new Set(
+    [...html.matchAll(/(?:href|src)="([^"]+)"/g)]
+      .map((match) => new URL(match[1], self.registration.scope))
+      .filter((url) => url.origin === self.location.origin)
+      .map((url) => url.href)
+  );
+  await cache.addAll([...assets]);
+  await cache.put("./", page);
+
+  const stale = (await cache.keys()).filter((request) => request.url !== pageUrl && !assets.has(request.url));
+  await Promise.all(stale.map((request) => cache.delete(request)));
+}
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(
+    (async () => {
+      const cache = await caches.open(CACHE);
+      await precache(cache, await fetch("./", { cache: "no-cache" }));
+      await self.skipWaiting();
+    })()
+  );
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    (async () => {
+      const names = await caches.keys();
+      await Promise.all(names.filter((name) => name !== CACHE).map((name) => caches.delete(name)));
+      await self.clients.claim();
+    })()
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  if (event.request.method !== "GET") {
+    return;
+  }
+  const page = event.request.mode === "navigate";
+
+  event.respondWith(
+    (async () => {
+      const cache = await caches.open(CACHE);
+      const cached = await cache.match(page ? "./" : event.request);
+
+      // Refresh the cache in the background, with the assets of a changed page before the page itself
+      const update = fetch(event.request).then(async (response) => {
+        if (page && response.ok) {
+          await precache(cache, response.clone());
+        } else if (response.ok || response.type === "opaque") {
+          await cache.put(event.request, response.clone());
+        }
+        return response;
+      });
+
+      if (cached) {
+        event.waitUntil(update.catch(() => {}));
+        return cached;
+      }
+      return update;
+    })()
+  );
+});
@@ -23,7 +23,8 @@ module.exports = {
         },
       },
       fontFamily: {
-        'display': ['Major Mono Display', 'monospace'],
+        // A system font, as a downloaded one wouldn't load offline
+        'display': ['ui-monospace', 'SFMono-Regular', 'Menlo', 'Consolas', 'monospace'],
       },
       width: {
         '13': '3.25rem',